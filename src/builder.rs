@@ -1,37 +1,187 @@
 use std::{
-    collections::HashMap,
+    cell::RefCell,
+    cmp::Reverse,
+    collections::BTreeMap,
     env,
-    ffi::OsString,
+    ffi::{OsStr, OsString},
     fmt,
-    fs::{read_to_string, write, File},
-    io::{BufReader, Read},
+    fs::{copy, create_dir_all, read, read_dir, read_to_string, remove_dir_all, write, File},
+    hash::{Hash, Hasher},
+    io::{BufReader, ErrorKind, Read},
+    iter,
+    panic::resume_unwind,
     path::{Path, PathBuf},
-    sync::LazyLock,
+    str::FromStr,
+    sync::{mpsc, LazyLock},
+    thread,
+    time::{Duration, Instant},
 };
 
 use regex::Regex;
+use semver::Version;
+use sha2::{Digest, Sha256};
 
 use crate::{
-    error::{BuildErrorKind, Error, Result, ResultExt},
-    executable::{Cargo, ExecutableRunner},
-    source::Crate,
+    error::{BuildErrorKind, Diagnostic, Error, Result, ResultExt},
+    executable::{
+        Cargo, CudaToolkit, Executable, ExecutableRunner, Fatbinary, Output, PtxLinker, Ptxas,
+        Rustc,
+    },
+    message::{Artifact, CargoMessage},
+    ptx,
+    source::{Crate, StableHasher},
 };
 
 const LAST_BUILD_CMD: &str = ".last-build-command";
-const TARGET_NAME: &str = "nvptx64-nvidia-cuda";
+const DEPENDENCIES_HASH: &str = ".dependencies-hash";
+const LAST_USED: &str = ".last-used";
+const RECURSION_GUARD: &str = "PTX_CRATE_BUILDING";
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Well-known `cargo` output lines, that are dropped by default.
+const NOISE_LINES: &[&str] = &[
+    "Blocking waiting for file lock",
+    "Compiling core ",
+    "Compiling compiler_builtins ",
+    "Compiling rustc-std-workspace-core ",
+    "Compiling alloc ",
+];
+
+/// Crate types, that can be forced with `rustc --crate-type`.
+const RUSTC_CRATE_TYPES: &[&str] = &["lib", "cdylib", "bin", "rlib"];
+
+/// PTX ISA versions, that the LLVM NVPTX backend can emit.
+const PTX_VERSIONS: &[(u32, u32)] = &[
+    (3, 2),
+    (4, 0),
+    (4, 1),
+    (4, 2),
+    (4, 3),
+    (5, 0),
+    (6, 0),
+    (6, 1),
+    (6, 2),
+    (6, 3),
+    (6, 4),
+    (6, 5),
+    (7, 0),
+    (7, 1),
+    (7, 2),
+    (7, 3),
+    (7, 4),
+    (7, 5),
+    (7, 6),
+    (7, 7),
+    (7, 8),
+    (8, 0),
+    (8, 1),
+    (8, 2),
+    (8, 3),
+    (8, 4),
+    (8, 5),
+    (8, 6),
+    (8, 7),
+];
 
 /// Core of the crate - PTX assembly build controller.
+///
+/// Builders never modify the process environment: their variables are
+/// passed to the `cargo` child process directly. So several builds can run
+/// concurrently from different threads.
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Builder {
     source_crate: Crate,
+    package: Option<String>,
+    toolchain: Option<String>,
+    target_dir: Option<PathBuf>,
+    sysroot: Option<PathBuf>,
+    output_name: Option<String>,
 
     profile: Profile,
+    target: NvptxTarget,
+    output_kind: OutputKind,
+    opt_level: Option<OptLevel>,
+    lto: Option<LtoMode>,
+    panic_strategy: Option<PanicStrategy>,
+    debug_info: Option<DebugInfo>,
+    codegen_units: Option<u32>,
+    overflow_checks: Option<bool>,
+    ptx_version: Option<(u32, u32)>,
+    compute_capabilities: Vec<ComputeCapability>,
+    rustc_flags: Vec<String>,
+    unstable_flags: Vec<String>,
+    cargo_args: Vec<String>,
+    cargo_config: Vec<String>,
+    build_std: Vec<String>,
+    build_std_features: Vec<String>,
+    features: Vec<String>,
+    default_features: bool,
+    all_features: bool,
+    emit_cubin: bool,
+    relocatable: bool,
+    validate_ptx: bool,
+    require_kernels: bool,
+    deny_warnings: bool,
+    keep_intermediates: bool,
+    incremental: Option<bool>,
+    retries: u32,
+    cache_limit: Option<usize>,
+    check_linker: bool,
+    linker: Option<String>,
+    offline: bool,
+    jobs: Option<u32>,
+    frozen: bool,
     colors: bool,
     crate_type: Option<CrateType>,
+    rustc_crate_type: Option<String>,
+    bin: Option<String>,
+    example: Option<String>,
     message_format: MessageFormat,
+    verbosity: Verbosity,
     prefix: String,
+    recursion_guard: String,
+
+    env: BTreeMap<OsString, OsString>,
+    kept_env: Vec<OsString>,
+    progress: Option<ProgressCallback>,
+    ptx_postprocessor: Option<PtxPostprocessor>,
+    diagnostic_filter: Option<DiagnosticFilter>,
+}
+
+struct ProgressCallback(Box<dyn Fn(BuildEvent) + Send + Sync>);
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("ProgressCallback")
+    }
+}
+
+struct PtxPostprocessor(Box<dyn Fn(String) -> Result<String> + Send + Sync>);
+
+impl fmt::Debug for PtxPostprocessor {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("PtxPostprocessor")
+    }
+}
+
+struct DiagnosticFilter(Box<dyn Fn(&str) -> bool + Send + Sync>);
+
+impl fmt::Debug for DiagnosticFilter {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("DiagnosticFilter")
+    }
+}
 
-    env: HashMap<OsString, OsString>,
+/// Reason of a skipped build.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SkipReason {
+    /// `build.rs` script was called by **RLS**.
+    Rls,
+
+    /// `build.rs` was called **recursively**, i.e. `PTX_CRATE_BUILDING=1` or
+    /// the configured recursion guard is set.
+    RecursiveBuild,
 }
 
 /// Successful build output.
@@ -40,6 +190,136 @@ pub struct BuildOutput<'a> {
     builder: &'a Builder,
     output_path: PathBuf,
     file_suffix: String,
+    assembly_path: Option<PathBuf>,
+    fatbin_path: Option<PathBuf>,
+    was_cached: bool,
+    build_duration: Duration,
+    dependency_crates: Vec<(String, Version)>,
+    warnings: Vec<Diagnostic>,
+    cargo_output: Option<Box<Output>>,
+}
+
+/// Resource usage of a kernel, as reported by `ptxas`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct KernelResourceUsage {
+    /// Name of the kernel.
+    pub name: String,
+
+    /// Number of used registers per thread.
+    pub registers: u32,
+
+    /// Size of the statically allocated shared memory.
+    pub shared_bytes: u64,
+
+    /// Size of the local memory per thread.
+    pub local_bytes: u64,
+
+    /// Size of the used constant memory in all banks.
+    pub const_bytes: u64,
+}
+
+/// Signature of a kernel, as declared in the PTX assembly.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct KernelSignature {
+    /// Name of the kernel.
+    pub name: String,
+
+    /// Kernel parameters, in declaration order.
+    pub params: Vec<KernelParam>,
+}
+
+/// Linkage of a kernel entry in the PTX assembly.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum KernelVisibility {
+    /// Kernel is exported, e.g. declared as `.visible .entry`.
+    Visible,
+
+    /// Kernel is local to the PTX module, i.e. declared as plain `.entry`.
+    Local,
+}
+
+/// Parameter of a kernel, as declared in the PTX assembly.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct KernelParam {
+    /// Name of the parameter, e.g. `the_kernel_param_0`.
+    pub name: String,
+
+    /// PTX type of the parameter, e.g. `.u64` or `.f64`.
+    pub ty: String,
+
+    /// Number of elements, when the parameter is passed as a byte array,
+    /// e.g. a struct by value.
+    pub array_len: Option<usize>,
+}
+
+/// Persistable metadata of a successful build.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BuildMetadata {
+    /// Path to the PTX assembly file.
+    pub assembly_path: PathBuf,
+
+    /// Source files the build depends on.
+    pub dependencies: Vec<PathBuf>,
+
+    /// Names of the kernels in the PTX assembly.
+    pub kernels: Vec<String>,
+}
+
+/// Summary of a successful build, e.g. for CI artifacts.
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BuildReport {
+    /// Name of the built crate.
+    pub crate_name: String,
+
+    /// Version of the built crate.
+    pub crate_version: String,
+
+    /// Build profile, e.g. `release`.
+    pub profile: String,
+
+    /// Target triple, e.g. `nvptx64-nvidia-cuda`.
+    pub target: String,
+
+    /// Requested compute capabilities, e.g. `sm_86`.
+    pub compute_capabilities: Vec<String>,
+
+    /// Path to the PTX assembly file.
+    pub assembly_path: PathBuf,
+
+    /// Names of the kernels in the PTX assembly.
+    pub kernels: Vec<String>,
+
+    /// Source files the build depends on.
+    pub dependencies: Vec<PathBuf>,
+
+    /// Whether the cached build was up-to-date.
+    pub was_cached: bool,
+
+    /// How long `cargo` took to build the crate.
+    pub build_duration: Duration,
+}
+
+/// Build defaults from the `[package.metadata.ptx]` table of the crate
+/// manifest.
+///
+/// ```toml
+/// [package.metadata.ptx]
+/// compute-capabilities = ["sm_61", "sm_75"]
+/// profile = "debug"
+/// features = ["fast-math"]
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct PtxMetadata {
+    /// Compute capabilities to build for.
+    pub compute_capabilities: Vec<ComputeCapability>,
+
+    /// Build profile, where `dev` and `debug` mean the debug profile.
+    pub profile: Option<Profile>,
+
+    /// Crate features to enable.
+    pub features: Vec<String>,
 }
 
 /// Non-failed build status.
@@ -52,6 +332,9 @@ pub enum BuildStatus<'a> {
     /// - `build.rs` script was called by **RLS**,
     /// - `build.rs` was called **recursively** (e.g. `build.rs` call for device
     ///   crate in single-source setup)
+    ///
+    /// The exact reason is provided by
+    /// [`Builder::build_skip_reason`](struct.Builder.html#method.build_skip_reason).
     NotNeeded,
 }
 
@@ -76,6 +359,207 @@ pub enum Profile {
 
     /// Equivalent for `cargo-build` **with** `--release` flag.
     Release,
+
+    /// Equivalent for `cargo-build` with `--profile <name>` flag.
+    Custom(String),
+}
+
+/// NVPTX target triple.
+///
+/// # Usage
+/// ``` no_run
+/// use ptx_builder::prelude::*;
+/// # use ptx_builder::error::Result;
+///
+/// # fn main() -> Result<()> {
+/// Builder::new(".")?
+///     .set_target(NvptxTarget::Nvptx64)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum NvptxTarget {
+    /// Equivalent for `cargo-build` with `--target nvptx-nvidia-cuda` flag.
+    ///
+    /// The 32-bit target is not shipped with recent Rust toolchains anymore.
+    Nvptx32,
+
+    /// Equivalent for `cargo-build` with `--target nvptx64-nvidia-cuda` flag
+    /// (default).
+    Nvptx64,
+}
+
+/// Kind of the produced artifact.
+///
+/// # Usage
+/// ``` no_run
+/// use ptx_builder::prelude::*;
+/// # use ptx_builder::error::Result;
+///
+/// # fn main() -> Result<()> {
+/// Builder::new(".")?
+///     .set_output_type(OutputKind::LlvmIr)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum OutputKind {
+    /// PTX assembly `.ptx` file (default).
+    Ptx,
+
+    /// LLVM-IR `.ll` file, equivalent for `rustc` with `--emit=llvm-ir` flag.
+    LlvmIr,
+
+    /// LLVM bitcode `.bc` file, equivalent for `rustc` with `--emit=llvm-bc`
+    /// flag.
+    LlvmBc,
+}
+
+/// Optimization level, independent of the [`Profile`](enum.Profile.html).
+///
+/// # Usage
+/// ``` no_run
+/// use ptx_builder::prelude::*;
+/// # use ptx_builder::error::Result;
+///
+/// # fn main() -> Result<()> {
+/// Builder::new(".")?
+///     .set_profile(Profile::Release)
+///     .set_opt_level(OptLevel::O2)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum OptLevel {
+    /// Equivalent for `rustc` with `-C opt-level=0` flag.
+    O0,
+
+    /// Equivalent for `rustc` with `-C opt-level=1` flag.
+    O1,
+
+    /// Equivalent for `rustc` with `-C opt-level=2` flag.
+    O2,
+
+    /// Equivalent for `rustc` with `-C opt-level=3` flag.
+    O3,
+
+    /// Equivalent for `rustc` with `-C opt-level=s` flag.
+    Os,
+
+    /// Equivalent for `rustc` with `-C opt-level=z` flag.
+    Oz,
+}
+
+/// Link-time optimization mode.
+///
+/// # Usage
+/// ``` no_run
+/// use ptx_builder::prelude::*;
+/// # use ptx_builder::error::Result;
+///
+/// # fn main() -> Result<()> {
+/// Builder::new(".")?
+///     .set_lto(LtoMode::Fat)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum LtoMode {
+    /// Equivalent for `rustc` with `-C lto=off` flag.
+    Off,
+
+    /// Equivalent for `rustc` with `-C lto=thin` flag.
+    Thin,
+
+    /// Equivalent for `rustc` with `-C lto=fat` flag.
+    Fat,
+}
+
+/// Panic strategy.
+///
+/// # Usage
+/// ``` no_run
+/// use ptx_builder::prelude::*;
+/// # use ptx_builder::error::Result;
+///
+/// # fn main() -> Result<()> {
+/// Builder::new(".")?
+///     .set_panic_strategy(PanicStrategy::Abort)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum PanicStrategy {
+    /// Equivalent for `rustc` with `-C panic=abort` flag.
+    Abort,
+
+    /// Equivalent for `rustc` with `-C panic=unwind` flag.
+    Unwind,
+}
+
+/// Debug info level, independent of the [`Profile`](enum.Profile.html).
+///
+/// # Usage
+/// ``` no_run
+/// use ptx_builder::prelude::*;
+/// # use ptx_builder::error::Result;
+///
+/// # fn main() -> Result<()> {
+/// Builder::new(".")?
+///     .set_debug_info(DebugInfo::LineTables)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum DebugInfo {
+    /// Equivalent for `rustc` with `-C debuginfo=0` flag.
+    None,
+
+    /// Equivalent for `rustc` with `-C debuginfo=line-tables-only` flag.
+    LineTables,
+
+    /// Equivalent for `rustc` with `-C debuginfo=2` flag.
+    Full,
+}
+
+/// CUDA compute capability (SM architecture) to generate PTX for.
+///
+/// # Usage
+/// ``` no_run
+/// use ptx_builder::prelude::*;
+/// # use ptx_builder::error::Result;
+///
+/// # fn main() -> Result<()> {
+/// Builder::new(".")?
+///     .set_compute_capability("sm_86".parse()?)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum ComputeCapability {
+    Sm35,
+    Sm37,
+    Sm50,
+    Sm52,
+    Sm53,
+    Sm60,
+    Sm61,
+    Sm62,
+    Sm70,
+    Sm72,
+    Sm75,
+    Sm80,
+    Sm86,
+    Sm87,
+    Sm89,
+    Sm90,
 }
 
 /// Message format.
@@ -115,6 +599,62 @@ pub enum MessageFormat {
     Short,
 }
 
+/// Verbosity of the `cargo` output.
+///
+/// # Usage
+/// ``` no_run
+/// use ptx_builder::prelude::*;
+/// # use ptx_builder::error::Result;
+///
+/// # fn main() -> Result<()> {
+/// Builder::new(".")?
+///     .set_verbosity(Verbosity::Verbose)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Verbosity {
+    /// Equivalent for `cargo-build` with `-q` flag.
+    ///
+    /// The `rustc` invocations are hidden, so the assembly path is only
+    /// taken from the artifact messages of `cargo`.
+    Quiet,
+
+    /// Equivalent for `cargo-build` with `-v` flag (default).
+    Normal,
+
+    /// Equivalent for `cargo-build` with `-vv` flag, which also shows the
+    /// build script output.
+    Verbose,
+}
+
+/// Progress event of the build.
+///
+/// # Usage
+/// ``` no_run
+/// use ptx_builder::prelude::*;
+/// # use ptx_builder::error::Result;
+///
+/// # fn main() -> Result<()> {
+/// Builder::new(".")?
+///     .with_progress(|event| eprintln!("{event:?}"))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum BuildEvent {
+    /// The `cargo` build was started.
+    Started,
+
+    /// `cargo` started to compile the crate with the given name.
+    CompilingCrate(String),
+
+    /// The `cargo` build was finished successfully.
+    Finished,
+}
+
 /// Build specified crate type.
 ///
 /// Mandatory for mixed crates - that have both `lib.rs` and `main.rs`,
@@ -136,14 +676,40 @@ pub enum MessageFormat {
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum CrateType {
     Library,
     Binary,
+
+    /// Leaves the choice to the builder, same as not setting a crate type.
+    Mixed,
+}
+
+/// Kind of a [`CrateTarget`](struct.CrateTarget.html).
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CrateTargetKind {
+    Library,
+    Binary,
+    Example,
+}
+
+/// Build target of the crate, either declared in `Cargo.toml` or
+/// discovered at the conventional location.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CrateTarget {
+    /// Name of the target, e.g. for `--bin` and `--example`.
+    pub name: String,
+
+    /// Kind of the target.
+    pub kind: CrateTargetKind,
+
+    /// Path of the target root source file, relative to the crate.
+    pub path: PathBuf,
 }
 
 impl Builder {
-    /// Construct a builder for device crate at `path`.
+    /// Construct a builder for device crate at `path`, e.g. a `&str` or a
+    /// `PathBuf`.
     ///
     /// Can also be the same crate, for single-source mode:
     /// ``` no_run
@@ -164,38 +730,213 @@ impl Builder {
     /// # }
     /// ```
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        Ok(Builder {
-            source_crate: Crate::analyse(path).context("Unable to analyse source crate")?,
-            // TODO: choose automatically, e.g.:
-            // `env::var("PROFILE").unwrap_or("release".to_string())`
-            profile: Profile::Release,
-            colors: true,
-            crate_type: None,
-            message_format: MessageFormat::Human,
-            prefix: String::new(),
-            env: HashMap::new(),
-        })
+        Ok(Self::from_source_crate(
+            Crate::analyse(path).context("Unable to analyse source crate")?,
+            None,
+        ))
     }
 
-    /// Returns bool indicating whether the actual build is needed.
+    /// Construct a builder for the nearest device crate, walking up from
+    /// `CARGO_MANIFEST_DIR` or the current directory.
     ///
-    /// Behavior is consistent with
-    /// [`BuildStatus::NotNeeded`](enum.BuildStatus.html#variant.NotNeeded).
-    #[must_use]
-    pub fn is_build_needed() -> bool {
-        let recursive_env = env::var("PTX_CRATE_BUILDING");
+    /// The device crate is the first one, that enables the `abi_ptx`
+    /// feature:
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::locate()?.build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn locate() -> Result<Self> {
+        let start =
+            env::var_os("CARGO_MANIFEST_DIR").map_or_else(|| PathBuf::from("."), PathBuf::from);
 
-        let is_recursive_build = recursive_env.map_or(false, |recursive_env| recursive_env == "1");
+        Ok(Self::from_source_crate(Crate::locate(start)?, None))
+    }
 
-        !is_recursive_build
+    /// Construct a builder for device crate with the `manifest` file.
+    ///
+    /// The crate root is the directory of the manifest:
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::from_manifest("kernel/Cargo.toml")?.build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_manifest<P: AsRef<Path>>(manifest: P) -> Result<Self> {
+        Ok(Self::from_source_crate(
+            Crate::analyse_manifest(manifest).context("Unable to analyse source crate")?,
+            None,
+        ))
     }
 
-    /// Returns the name of the source crate at the construction `path`.
-    #[must_use]
+    /// Construct a builder for the `package` device crate inside the
+    /// workspace at `path`.
+    ///
+    /// Mandatory for virtual workspaces, i.e. those without a root crate:
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::with_package(".", "kernel")?.build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_package<P: AsRef<Path>>(path: P, package: &str) -> Result<Self> {
+        Ok(Self::from_source_crate(
+            Crate::analyse_workspace_member(path, package)
+                .context("Unable to analyse source crate")?,
+            Some(String::from(package)),
+        ))
+    }
+
+    fn from_source_crate(source_crate: Crate, package: Option<String>) -> Self {
+        let metadata = source_crate.get_ptx_metadata().clone();
+
+        Builder {
+            source_crate,
+            package,
+            toolchain: None,
+            target_dir: None,
+            sysroot: None,
+            output_name: None,
+            // TODO: choose automatically, e.g.:
+            // `env::var("PROFILE").unwrap_or("release".to_string())`
+            profile: metadata.profile.unwrap_or(Profile::Release),
+            target: NvptxTarget::Nvptx64,
+            output_kind: OutputKind::Ptx,
+            opt_level: None,
+            lto: None,
+            panic_strategy: None,
+            debug_info: None,
+            codegen_units: None,
+            overflow_checks: None,
+            ptx_version: None,
+            compute_capabilities: metadata.compute_capabilities,
+            rustc_flags: Vec::new(),
+            unstable_flags: Vec::new(),
+            cargo_args: Vec::new(),
+            cargo_config: Vec::new(),
+            build_std: Vec::new(),
+            build_std_features: Vec::new(),
+            features: metadata.features,
+            default_features: true,
+            all_features: false,
+            emit_cubin: false,
+            relocatable: false,
+            validate_ptx: false,
+            require_kernels: false,
+            deny_warnings: false,
+            keep_intermediates: false,
+            incremental: None,
+            retries: 0,
+            cache_limit: None,
+            check_linker: false,
+            linker: None,
+            offline: false,
+            jobs: None,
+            frozen: false,
+            colors: true,
+            crate_type: None,
+            rustc_crate_type: None,
+            bin: None,
+            example: None,
+            message_format: MessageFormat::Human,
+            verbosity: Verbosity::Normal,
+            prefix: String::new(),
+            recursion_guard: String::from(RECURSION_GUARD),
+            env: BTreeMap::new(),
+            kept_env: Vec::new(),
+            progress: None,
+            ptx_postprocessor: None,
+            diagnostic_filter: None,
+        }
+    }
+
+    /// Returns bool indicating whether the actual build is needed.
+    ///
+    /// Behavior is consistent with
+    /// [`BuildStatus::NotNeeded`](enum.BuildStatus.html#variant.NotNeeded).
+    #[must_use]
+    pub fn is_build_needed() -> bool {
+        Self::build_skip_reason().is_none()
+    }
+
+    /// Returns the reason why the actual build is not needed, if any.
+    ///
+    /// Only the default recursion guard `PTX_CRATE_BUILDING` is checked, see
+    /// [`Builder::get_skip_reason`] for the configured one.
+    #[must_use]
+    pub fn build_skip_reason() -> Option<SkipReason> {
+        Self::get_skip_reason_with(RECURSION_GUARD)
+    }
+
+    /// Returns the reason why the actual build is not needed, if any,
+    /// with respect to the configured recursion guard.
+    #[must_use]
+    pub fn get_skip_reason(&self) -> Option<SkipReason> {
+        Self::get_skip_reason_with(&self.recursion_guard)
+    }
+
+    fn get_skip_reason_with(recursion_guard: &str) -> Option<SkipReason> {
+        let recursive_env = env::var(recursion_guard);
+
+        if recursive_env.is_ok_and(|recursive_env| recursive_env == "1") {
+            return Some(SkipReason::RecursiveBuild);
+        }
+
+        let cargo_env = env::var_os("CARGO");
+
+        if cargo_env.is_some_and(|cargo| Path::new(&cargo).file_stem() == Some(OsStr::new("rls"))) {
+            return Some(SkipReason::Rls);
+        }
+
+        None
+    }
+
+    /// Returns the name of the source crate at the construction `path`.
+    #[must_use]
     pub fn get_crate_name(&self) -> &str {
         self.source_crate.get_name()
     }
 
+    /// Returns the build defaults from `[package.metadata.ptx]` of the
+    /// source crate.
+    ///
+    /// The builder starts with them: [`Builder::set_profile`](#method.set_profile)
+    /// and [`Builder::set_compute_capability`](#method.set_compute_capability)
+    /// override them, while [`Builder::with_features`](#method.with_features)
+    /// enables features in addition.
+    #[must_use]
+    pub fn get_ptx_metadata(&self) -> &PtxMetadata {
+        self.source_crate.get_ptx_metadata()
+    }
+
+    /// Returns the library, binary and example targets of the source crate.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// for target in Builder::new(".")?.targets()? {
+    ///     println!("{:?} `{}` at {}", target.kind, target.name, target.path.display());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn targets(&self) -> Result<Vec<CrateTarget>> {
+        self.source_crate.targets()
+    }
+
     /// Disable colors for internal calls to `cargo`.
     #[must_use]
     pub fn disable_colors(mut self) -> Self {
@@ -203,6 +944,119 @@ impl Builder {
         self
     }
 
+    /// Set the `rustup` toolchain that is used for the build.
+    ///
+    /// Equivalent for `cargo +<toolchain>`.
+    #[must_use]
+    pub fn with_toolchain(mut self, toolchain: &str) -> Self {
+        self.toolchain = Some(String::from(toolchain));
+        self
+    }
+
+    /// Set the directory for build artifacts instead of the `OUT_DIR`.
+    ///
+    /// Builds of the same crate and settings from several host crates can
+    /// share the directory.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_target_dir("../target/ptx")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_target_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.target_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set a custom sysroot, that provides `core` for the NVPTX target.
+    ///
+    /// The path is passed to `rustc` with `--sysroot`, and the build fails,
+    /// when it doesn't exist.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_sysroot("/opt/rust/nvptx-sysroot")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_sysroot<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.sysroot = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set the filename, without extension, of the final assembly.
+    ///
+    /// The assembly is copied next to the `cargo` output, so
+    /// [`BuildOutput::get_assembly_path`](struct.BuildOutput.html#method.get_assembly_path)
+    /// ends with e.g. `<name>.ptx`.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_output_name("kernels-sm70")
+    ///     .set_compute_capability(ComputeCapability::Sm70)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_output_name(mut self, name: &str) -> Self {
+        self.output_name = Some(String::from(name));
+        self
+    }
+
+    /// Removes all cached builds of the crate.
+    ///
+    /// Does nothing, when the crate was never built.
+    pub fn clean(&self) -> Result<()> {
+        let output_root = self
+            .source_crate
+            .get_output_root(self.target_dir.as_deref());
+
+        match remove_dir_all(output_root) {
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(()),
+            result => result.context("Unable to remove output path"),
+        }
+    }
+
+    /// Returns the output directories of all cached builds of the crate,
+    /// e.g. with different settings.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// for path in Builder::new(".")?.cached_builds()? {
+    ///     println!("cached build: {}", path.display());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cached_builds(&self) -> Result<Vec<PathBuf>> {
+        self.source_crate.cached_builds(self.target_dir.as_deref())
+    }
+
     /// Set build profile.
     #[must_use]
     pub fn set_profile(mut self, profile: Profile) -> Self {
@@ -210,160 +1064,1604 @@ impl Builder {
         self
     }
 
-    /// Set crate type that needs to be built.
+    /// Set the NVPTX target triple.
+    #[must_use]
+    pub fn set_target(mut self, target: NvptxTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Set the kind of the produced artifact.
+    #[must_use]
+    pub fn set_output_type(mut self, output_kind: OutputKind) -> Self {
+        self.output_kind = output_kind;
+        self
+    }
+
+    /// Set the optimization level, overriding the default of the build
+    /// profile.
     ///
-    /// Mandatory for mixed crates - that have both `lib.rs` and `main.rs`,
-    /// otherwise Cargo won't know which to build:
-    /// ```text
-    /// error: extra arguments to `rustc` can only be passed to one target, consider filtering
-    /// the package by passing e.g. `--lib` or `--bin NAME` to specify a single target
-    /// ```
+    /// The artifacts are still placed into the build profile's directory.
     #[must_use]
-    pub fn set_crate_type(mut self, crate_type: CrateType) -> Self {
-        self.crate_type = Some(crate_type);
+    pub fn set_opt_level(mut self, opt_level: OptLevel) -> Self {
+        self.opt_level = Some(opt_level);
         self
     }
 
-    /// Set the message format.
+    /// Set the link-time optimization mode, e.g. to inline functions from
+    /// dependency crates into the kernels.
     #[must_use]
-    pub fn set_message_format(mut self, message_format: MessageFormat) -> Self {
-        self.message_format = message_format;
+    pub fn set_lto(mut self, lto: LtoMode) -> Self {
+        self.lto = Some(lto);
         self
     }
 
-    /// Set the build command prefix.
+    /// Set the panic strategy of the crate.
+    ///
+    /// The NVPTX targets abort on panic by default, so the strategy is only
+    /// passed to `rustc` when set explicitly. Precompiled `core` is used
+    /// as-is, unless rebuilt with
+    /// [`Builder::with_build_std`](struct.Builder.html#method.with_build_std).
     #[must_use]
-    pub fn set_prefix(mut self, prefix: String) -> Self {
-        self.prefix = prefix;
+    pub fn set_panic_strategy(mut self, panic_strategy: PanicStrategy) -> Self {
+        self.panic_strategy = Some(panic_strategy);
         self
     }
 
-    /// Inserts or updates an environment variable for the build process.
+    /// Set the debug info level, overriding the default of the build profile.
+    ///
+    /// Line tables emit `.loc` and `.file` directives into the PTX, as used
+    /// by profilers like Nsight Compute.
     #[must_use]
-    pub fn with_env<K: Into<OsString>, V: Into<OsString>>(mut self, key: K, val: V) -> Self {
-        self.env.insert(key.into(), val.into());
+    pub fn set_debug_info(mut self, debug_info: DebugInfo) -> Self {
+        self.debug_info = Some(debug_info);
         self
     }
 
-    /// Performs an actual build: runs `cargo` with proper flags and
-    /// environment.
-    pub fn build(&self) -> Result<BuildStatus> {
-        self.build_live(|_line| (), |_line| ())
+    /// Set the number of codegen units, overriding the default of the build
+    /// profile.
+    ///
+    /// A single unit allows the most inlining and gives deterministic PTX.
+    #[must_use]
+    pub fn set_codegen_units(mut self, codegen_units: u32) -> Self {
+        self.codegen_units = Some(codegen_units);
+        self
     }
 
-    /// Performs an actual build: runs `cargo` with proper flags and
-    /// environment.
-    pub fn build_live<O: FnMut(&str), E: FnMut(&str)>(
-        &self,
-        on_stdout_line: O,
-        mut on_stderr_line: E,
-    ) -> Result<BuildStatus> {
-        if !Self::is_build_needed() {
-            return Ok(BuildStatus::NotNeeded);
-        }
+    /// Enable or disable integer overflow checks, overriding the default of
+    /// the build profile.
+    ///
+    /// An overflow calls the panic handler of the crate, e.g. to catch index
+    /// bugs of release builds during testing.
+    #[must_use]
+    pub fn set_overflow_checks(mut self, on: bool) -> Self {
+        self.overflow_checks = Some(on);
+        self
+    }
 
-        let mut cargo = ExecutableRunner::new(Cargo);
-        let mut args = vec!["rustc"];
+    /// Set the PTX ISA version of the assembly, i.e. its `.version` header.
+    ///
+    /// Equivalent for `rustc` with `-C target-feature=+ptx<major><minor>`
+    /// flag. LLVM raises the version, when the compute capability requires
+    /// a newer one. The build fails for versions, that LLVM can't emit.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .set_ptx_version(7, 0)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn set_ptx_version(mut self, major: u32, minor: u32) -> Self {
+        self.ptx_version = Some((major, minor));
+        self
+    }
 
-        if self.profile == Profile::Release {
-            args.push("--release");
-        }
+    /// Set the compute capability to generate PTX for.
+    ///
+    /// Equivalent for passing `-C target-cpu=sm_XX` to `rustc`.
+    #[must_use]
+    pub fn set_compute_capability(mut self, compute_capability: ComputeCapability) -> Self {
+        self.compute_capabilities = vec![compute_capability];
+        self
+    }
 
-        args.push("--color");
-        args.push(if self.colors { "always" } else { "never" });
+    /// Append extra flags that are passed to `rustc`.
+    ///
+    /// The flags are split at whitespace and passed after the flags of the
+    /// builder, so they can override them.
+    ///
+    /// Flags from `CARGO_ENCODED_RUSTFLAGS` or `RUSTFLAGS` environment
+    /// variables are passed to the build as well, but before the flags of the
+    /// builder - so the builder flags win on conflict.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_rustc_flag("-C no-vectorize-loops")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_rustc_flag(mut self, flag: &str) -> Self {
+        self.rustc_flags
+            .extend(flag.split_whitespace().map(String::from));
 
-        let mut json_format = String::from("--message-format=json");
-        args.push(match self.message_format {
-            MessageFormat::Human => "--message-format=human",
-            MessageFormat::Json {
-                render_diagnostics,
-                short,
-                ansi,
-            } => {
-                if render_diagnostics {
-                    json_format.push_str(",json-render-diagnostics");
-                }
+        self
+    }
 
-                if short {
-                    json_format.push_str(",json-diagnostic-short");
-                }
+    /// Append an unstable `-Z` flag that is passed to `rustc`.
+    ///
+    /// The unstable flags are passed right before the
+    /// [`Builder::with_rustc_flag`](#method.with_rustc_flag) flags. The
+    /// build fails, when a flag doesn't start with `-Z`.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_unstable_flag("-Z share-generics=off")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_unstable_flag(mut self, flag: &str) -> Self {
+        self.unstable_flags.push(flag.trim().to_string());
+        self
+    }
 
-                if ansi {
-                    json_format.push_str(",json-diagnostic-rendered-ansi");
-                }
+    /// Append an extra argument that is passed to `cargo`.
+    ///
+    /// The argument is passed after the flags of the builder, but before the
+    /// `--` separator of the `rustc` flags.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_cargo_arg("--locked")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_cargo_arg(mut self, arg: &str) -> Self {
+        self.cargo_args.push(String::from(arg));
+        self
+    }
+
+    /// Override a `cargo` configuration value, e.g. a profile setting.
+    ///
+    /// Equivalent for `cargo-build` with `--config <key>=<value>` flag. The
+    /// `value` is a TOML value, so strings have to be quoted.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_cargo_config("profile.release.codegen-units", "1")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_cargo_config(mut self, key: &str, value: &str) -> Self {
+        self.cargo_config.push(format!("{key}={value}"));
+        self
+    }
+
+    /// Build the standard library `crates` from source, with `features`.
+    ///
+    /// Equivalent for `cargo-build` with `-Z build-std` and
+    /// `-Z build-std-features` flags, so the `rust-src` component is
+    /// required. Allows device crates to use e.g. `alloc`.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_build_std(&["core", "alloc"], &["panic_immediate_abort"])
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_build_std(mut self, crates: &[&str], features: &[&str]) -> Self {
+        self.build_std = crates.iter().map(|name| String::from(*name)).collect();
+        self.build_std_features = features
+            .iter()
+            .map(|feature| String::from(*feature))
+            .collect();
+        self
+    }
+
+    /// Set several compute capabilities to generate PTX for.
+    ///
+    /// The PTX is built separately, in parallel, for each compute capability
+    /// and then combined into a single fat binary with `fatbinary`, which requires the
+    /// CUDA toolkit to be installed. The assembly of the first compute
+    /// capability is available as the main build output.
+    #[must_use]
+    pub fn set_compute_capabilities(mut self, compute_capabilities: &[ComputeCapability]) -> Self {
+        self.compute_capabilities = compute_capabilities.to_vec();
+        self
+    }
+
+    /// Enable additional crate features.
+    ///
+    /// Equivalent for `cargo-build` with `--features` flag.
+    #[must_use]
+    pub fn with_features(mut self, features: &[&str]) -> Self {
+        self.features
+            .extend(features.iter().map(|feature| String::from(*feature)));
+        self
+    }
+
+    /// Disable the default crate features.
+    ///
+    /// Equivalent for `cargo-build` with `--no-default-features` flag.
+    #[must_use]
+    pub fn disable_default_features(mut self) -> Self {
+        self.default_features = false;
+        self
+    }
+
+    /// Enable all crate features.
+    ///
+    /// Equivalent for `cargo-build` with `--all-features` flag. Takes
+    /// precedence over features enabled with
+    /// [`Builder::with_features`](struct.Builder.html#method.with_features).
+    #[must_use]
+    pub fn enable_all_features(mut self) -> Self {
+        self.all_features = true;
+        self
+    }
+
+    /// Assemble the PTX into a `.cubin` with `ptxas` after a successful build.
+    ///
+    /// Requires the CUDA toolkit to be installed. The cubin is generated for
+    /// the selected [`ComputeCapability`](enum.ComputeCapability.html), if
+    /// any.
+    #[must_use]
+    pub fn emit_cubin(mut self) -> Self {
+        self.emit_cubin = true;
+        self
+    }
+
+    /// Treat the PTX as relocatable device code, for separate compilation.
+    ///
+    /// Kernels may then call device functions, that are only declared as
+    /// `.extern` and defined in other modules. `ptxas` is run with
+    /// `--compile-only` for [`Builder::emit_cubin`](#method.emit_cubin) and
+    /// [`Builder::validate_ptx`](#method.validate_ptx), so the cubin has to
+    /// be linked with `nvlink` before it can be loaded.
+    #[must_use]
+    pub fn set_relocatable(mut self, on: bool) -> Self {
+        self.relocatable = on;
+        self
+    }
+
+    /// Check that `ptxas` accepts the PTX assembly after the build.
+    ///
+    /// The assembly is assembled for the (first) requested compute
+    /// capability, so the CUDA toolkit has to be installed. Rejected
+    /// assembly fails the build with the `ptxas` errors.
+    #[must_use]
+    pub fn validate_ptx(mut self) -> Self {
+        self.validate_ptx = true;
+        self
+    }
+
+    /// Fail the build, when the PTX assembly contains no kernels.
+    ///
+    /// Catches crates, that build fine but don't declare any
+    /// `extern "ptx-kernel"` function, so nothing can be launched.
+    #[must_use]
+    pub fn require_kernels(mut self) -> Self {
+        self.require_kernels = true;
+        self
+    }
+
+    /// Run `cargo` without accessing the network.
+    ///
+    /// Equivalent for `cargo-build` with `--offline` flag.
+    #[must_use]
+    pub fn set_offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
+    /// Run `cargo` without accessing the network and require that
+    /// `Cargo.lock` is up-to-date.
+    ///
+    /// Equivalent for `cargo-build` with `--frozen` flag.
+    #[must_use]
+    pub fn set_frozen(mut self) -> Self {
+        self.frozen = true;
+        self
+    }
+
+    /// Limit the number of parallel jobs of the build.
+    ///
+    /// Equivalent for `cargo-build` with `--jobs` flag. The output is the
+    /// same, so cached builds are reused regardless of the number.
+    #[must_use]
+    pub fn with_jobs(mut self, jobs: u32) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
+    /// Treat warnings of the crate as errors.
+    ///
+    /// Equivalent for passing `-D warnings` to `rustc`. The flag only applies
+    /// to the device crate and is passed before the
+    /// [`Builder::with_rustc_flag`] flags.
+    #[must_use]
+    pub fn deny_warnings(mut self) -> Self {
+        self.deny_warnings = true;
+        self
+    }
+
+    /// Keep the intermediate build files for inspection.
+    ///
+    /// Equivalent for passing `-C save-temps` to `rustc`. The output path is
+    /// reported with a note on every build and isn't evicted by the
+    /// [`Builder::with_cache_limit`] cleanup after the build.
+    /// An explicit [`Builder::clean`] still removes it.
+    #[must_use]
+    pub fn keep_intermediates(mut self) -> Self {
+        self.keep_intermediates = true;
+        self
+    }
+
+    /// Enable or disable incremental compilation, instead of the profile
+    /// default.
+    ///
+    /// Equivalent for running `cargo-build` with `CARGO_INCREMENTAL=1` or
+    /// `CARGO_INCREMENTAL=0`. The incremental cache is kept in the output
+    /// path, so [`Builder::clean`] removes it.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .set_profile(Profile::Debug)
+    ///     .set_incremental(true)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn set_incremental(mut self, on: bool) -> Self {
+        self.incremental = Some(on);
+        self
+    }
+
+    /// Retry `cargo` up to `count` times, when it fails with a transient
+    /// network error, e.g. while updating the registry index.
+    ///
+    /// The delay between the attempts doubles, starting with one second.
+    /// Failed builds of the crate itself are not retried.
+    #[must_use]
+    pub fn with_retries(mut self, count: u32) -> Self {
+        self.retries = count;
+        self
+    }
+
+    /// Keep at most `max_entries` cached builds of the crate.
+    ///
+    /// Every build configuration is cached in its own directory. After a
+    /// successful build, the least recently used ones beyond the limit are
+    /// removed. Builds for several compute capabilities use one entry per
+    /// capability and one more for the fatbin.
+    ///
+    /// The entries of the finished build are never removed, so the limit
+    /// is at least `1`.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_cache_limit(4)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_cache_limit(mut self, max_entries: usize) -> Self {
+        self.cache_limit = Some(max_entries.max(1));
+        self
+    }
+
+    /// Check that `rust-ptx-linker` is installed before the build.
+    ///
+    /// Only needed for setups with the legacy linker, the `llvm-bitcode-linker`
+    /// of recent toolchains is not checked.
+    #[must_use]
+    pub fn check_ptx_linker(mut self) -> Self {
+        self.check_linker = true;
+        self
+    }
+
+    /// Set the linker for the NVPTX target, e.g. the legacy
+    /// `rust-ptx-linker`, that is passed to `rustc` with `-C linker=`.
+    ///
+    /// Either a path or a command in `PATH`. The build fails, when it can't
+    /// be found.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_linker("rust-ptx-linker")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_linker(mut self, path: &str) -> Self {
+        self.linker = Some(String::from(path));
+        self
+    }
+
+    /// Set crate type that needs to be built.
+    ///
+    /// Mandatory for mixed crates - that have both `lib.rs` and `main.rs`,
+    /// otherwise Cargo won't know which to build:
+    /// ```text
+    /// error: extra arguments to `rustc` can only be passed to one target, consider filtering
+    /// the package by passing e.g. `--lib` or `--bin NAME` to specify a single target
+    /// ```
+    #[must_use]
+    pub fn set_crate_type(mut self, crate_type: CrateType) -> Self {
+        self.crate_type = match crate_type {
+            CrateType::Mixed => None,
+            crate_type => Some(crate_type),
+        };
+        self
+    }
+
+    /// Force the `rustc` crate type, e.g. `cdylib` or `lib`, of the built
+    /// target.
+    ///
+    /// Unlike [`Builder::set_crate_type`](#method.set_crate_type), which
+    /// selects the target, this replaces the `--crate-type` that is passed to
+    /// `rustc`. The build fails for other types than `lib`, `cdylib`, `bin`
+    /// and `rlib`.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_rustc_crate_type("cdylib")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_rustc_crate_type(mut self, crate_type: &str) -> Self {
+        self.rustc_crate_type = Some(String::from(crate_type));
+        self
+    }
+
+    /// Build the binary target with the given name.
+    ///
+    /// Equivalent for `cargo-build` with `--bin <name>` flag. Useful for
+    /// crates with several `[[bin]]` targets.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_bin("kernels")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_bin(mut self, name: &str) -> Self {
+        self.bin = Some(String::from(name));
+        self
+    }
+
+    /// Build the example target with the given name.
+    ///
+    /// Equivalent for `cargo-build` with `--example <name>` flag. Takes
+    /// precedence over [`Builder::with_bin`] and the crate type.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_example("saxpy")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_example(mut self, name: &str) -> Self {
+        self.example = Some(String::from(name));
+        self
+    }
+
+    /// Set the message format.
+    ///
+    /// With [`MessageFormat::Json`](enum.MessageFormat.html#variant.Json),
+    /// the JSON messages of `cargo` are forwarded and kept as they are in
+    /// [`BuildErrorKind::raw_lines`] of a failed build, e.g. for IDEs.
+    ///
+    /// [`BuildErrorKind::raw_lines`]: ../error/enum.BuildErrorKind.html#method.raw_lines
+    #[must_use]
+    pub fn set_message_format(mut self, message_format: MessageFormat) -> Self {
+        self.message_format = message_format;
+        self
+    }
+
+    /// Set the verbosity of the `cargo` output.
+    #[must_use]
+    pub fn set_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// Set the build command prefix.
+    #[must_use]
+    pub fn set_prefix(mut self, prefix: String) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Set the name of the environment variable that guards against
+    /// recursive builds, `PTX_CRATE_BUILDING` by default.
+    ///
+    /// Nested builders with distinct guards don't skip each other's builds.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_recursion_guard("KERNEL_A_BUILDING")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_recursion_guard(mut self, var: &str) -> Self {
+        self.recursion_guard = String::from(var);
+        self
+    }
+
+    /// Inserts or updates an environment variable for the build process.
+    ///
+    /// The recursion guard, `PTX_CRATE_BUILDING` by default, cannot be
+    /// overridden.
+    #[must_use]
+    pub fn with_env<K: Into<OsString>, V: Into<OsString>>(mut self, key: K, val: V) -> Self {
+        self.env.insert(key.into(), val.into());
+        self
+    }
+
+    /// Keeps an inherited environment variable, that would be removed from
+    /// the build process otherwise.
+    ///
+    /// See [`Builder::sanitized_env`](#method.sanitized_env) for the removed
+    /// variables.
+    #[must_use]
+    pub fn keep_inherited_env<K: Into<OsString>>(mut self, key: K) -> Self {
+        self.kept_env.push(key.into());
+        self
+    }
+
+    /// Returns the inherited environment variables, that are removed from
+    /// the build process.
+    ///
+    /// These are set by a parent `cargo` build, e.g. for a build script, and
+    /// would configure the nested build for the host: `CARGO_BUILD_*`,
+    /// `CARGO_MAKEFLAGS`, `RUSTC`, `RUSTDOC`, `RUSTC_WRAPPER` and
+    /// `RUSTC_WORKSPACE_WRAPPER`. Variables set with
+    /// [`Builder::with_env`](#method.with_env) or kept with
+    /// [`Builder::keep_inherited_env`](#method.keep_inherited_env) are not
+    /// removed. `RUSTUP_TOOLCHAIN` is kept as well, it selects the toolchain
+    /// unless [`Builder::with_toolchain`](#method.with_toolchain) is used.
+    #[must_use]
+    pub fn sanitized_env(&self) -> Vec<OsString> {
+        env::vars_os()
+            .map(|(key, _)| key)
+            .filter(|key| {
+                key.to_str().is_some_and(|key| {
+                    key.starts_with("CARGO_BUILD_")
+                        || matches!(
+                            key,
+                            "CARGO_MAKEFLAGS"
+                                | "RUSTC"
+                                | "RUSTDOC"
+                                | "RUSTC_WRAPPER"
+                                | "RUSTC_WORKSPACE_WRAPPER"
+                        )
+                })
+            })
+            .filter(|key| !self.env.contains_key(key) && !self.kept_env.contains(key))
+            .collect()
+    }
+
+    /// Set the callback that is notified about the build progress.
+    ///
+    /// The events are parsed from the live `cargo` output, regardless of
+    /// whether colors are enabled.
+    #[must_use]
+    pub fn with_progress<F: Fn(BuildEvent) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.progress = Some(ProgressCallback(Box::new(callback)));
+        self
+    }
+
+    /// Set the function that transforms the PTX assembly after a successful
+    /// build, e.g. to rewrite the version header.
+    ///
+    /// The `cargo` artifact is left untouched: the transformed assembly is
+    /// written next to the output path, where [`BuildOutput::get_assembly_path`]
+    /// points to. It doesn't apply to the LLVM IR or bitcode output.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_ptx_postprocessor(|ptx| Ok(ptx.replace(".version 6.0", ".version 7.0")))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_ptx_postprocessor<F>(mut self, postprocessor: F) -> Self
+    where
+        F: Fn(String) -> Result<String> + Send + Sync + 'static,
+    {
+        self.ptx_postprocessor = Some(PtxPostprocessor(Box::new(postprocessor)));
+        self
+    }
+
+    /// Set the predicate, that decides which `cargo` output lines are kept
+    /// for the forwarded output and [`BuildErrorKind::BuildFailed`].
+    ///
+    /// It replaces the default filter, that drops well-known noise, like
+    /// `Blocking waiting for file lock` and compiling the `core` crate.
+    ///
+    /// [`BuildErrorKind::BuildFailed`]: ../error/enum.BuildErrorKind.html#variant.BuildFailed
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// Builder::new(".")?
+    ///     .with_diagnostic_filter(|line| !line.contains("Compiling"))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_diagnostic_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.diagnostic_filter = Some(DiagnosticFilter(Box::new(filter)));
+        self
+    }
+
+    /// Returns the arguments that `cargo` is run with for the build.
+    ///
+    /// If several compute capabilities are set, the arguments for the first
+    /// one are returned.
+    pub fn get_cargo_args(&self) -> Result<Vec<String>> {
+        let crate_type = self.get_crate_type()?;
+
+        Ok(self.get_build_args(crate_type, self.compute_capabilities.first().copied()))
+    }
+
+    /// Returns the command line of the build without running it: the
+    /// environment variables as `KEY=VALUE`, followed by `cargo` and its
+    /// arguments.
+    ///
+    /// If several compute capabilities are set, the command for the first
+    /// one is returned.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let command = Builder::new(".")?.dry_run()?;
+    ///
+    /// println!("{}", command.join(" "));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dry_run(&self) -> Result<Vec<String>> {
+        let compute_capability = self.compute_capabilities.first().copied();
+        let crate_type = self.get_crate_type()?;
+
+        let output_path = self.source_crate.get_output_location(
+            self.target_dir.as_deref(),
+            &self.get_build_config(compute_capability),
+        );
+
+        let mut command = self
+            .env
+            .iter()
+            .filter(|(key, _)| {
+                !matches!(key.to_str(), Some("RUSTFLAGS" | "CARGO_ENCODED_RUSTFLAGS"))
+            })
+            .map(|(key, val)| format!("{}={}", key.to_string_lossy(), val.to_string_lossy()))
+            .collect::<Vec<_>>();
+
+        if let Some(rustflags) = self.get_env_rustflags() {
+            command.push(format!(
+                "CARGO_ENCODED_RUSTFLAGS={}",
+                rustflags.join("\x1f")
+            ));
+        }
+
+        if let Some(incremental) = self.get_incremental_env() {
+            command.push(format!("CARGO_INCREMENTAL={incremental}"));
+        }
+
+        command.push(format!("{}=1", self.recursion_guard));
+        command.push(format!("CARGO_TARGET_DIR={}", output_path.display()));
+        command.push(Cargo.get_name());
+        command.extend(self.get_build_args(crate_type, compute_capability));
+
+        Ok(command)
+    }
+
+    /// Performs an actual build: runs `cargo` with proper flags and
+    /// environment.
+    pub fn build(&self) -> Result<BuildStatus<'_>> {
+        self.build_live(|_line| (), |_line| ())
+    }
+
+    /// Performs an actual build: runs `cargo` with proper flags and
+    /// environment.
+    pub fn build_live<O: FnMut(&str), E: FnMut(&str)>(
+        &self,
+        on_stdout_line: O,
+        mut on_stderr_line: E,
+    ) -> Result<BuildStatus<'_>> {
+        if !self.prepare_build(&mut on_stderr_line)? {
+            return Ok(BuildStatus::NotNeeded);
+        }
+
+        let output = match self.compute_capabilities.as_slice() {
+            [] => self.build_for(None, on_stdout_line, on_stderr_line),
+            [compute_capability] => {
+                self.build_for(Some(*compute_capability), on_stdout_line, on_stderr_line)
+            }
+            compute_capabilities => {
+                let outputs = self
+                    .build_parallel(compute_capabilities, on_stdout_line, on_stderr_line)
+                    .into_iter()
+                    .collect::<Result<Vec<_>>>()?;
+
+                self.combine_outputs(compute_capabilities, outputs)
+            }
+        }?;
+
+        self.evict_cached_builds()?;
+
+        Ok(BuildStatus::Success(output))
+    }
+
+    /// Performs an actual build, while awaiting `cargo` on the `tokio`
+    /// runtime.
+    ///
+    /// The remaining steps, like the toolchain checks, are short and run
+    /// inline. Compute capabilities are built one after another.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # async fn build() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build_async().await? {
+    ///     println!("{}", output.get_assembly_path().display());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn build_async(&self) -> Result<BuildStatus<'_>> {
+        if !self.prepare_build(&mut |_line| ())? {
+            return Ok(BuildStatus::NotNeeded);
+        }
+
+        let output = match self.compute_capabilities.as_slice() {
+            [] => self.build_for_async(None).await?,
+            [compute_capability] => self.build_for_async(Some(*compute_capability)).await?,
+            compute_capabilities => {
+                let mut outputs = Vec::new();
+
+                for compute_capability in compute_capabilities {
+                    outputs.push(self.build_for_async(Some(*compute_capability)).await?);
+                }
+
+                self.combine_outputs(compute_capabilities, outputs)?
+            }
+        };
+
+        self.evict_cached_builds()?;
+
+        Ok(BuildStatus::Success(output))
+    }
+
+    /// Runs the checks, that precede every build.
+    ///
+    /// Returns `false`, when the build is not needed.
+    fn prepare_build<E: FnMut(&str)>(&self, on_stderr_line: &mut E) -> Result<bool> {
+        if self.get_skip_reason().is_some() {
+            return Ok(false);
+        }
+
+        if self.all_features && !self.features.is_empty() {
+            on_stderr_line(&format!(
+                "warning: all features are enabled, ignoring the explicit features `{}`",
+                self.features.join(",")
+            ));
+        }
+
+        self.check_ptx_version()?;
+        self.check_unstable_flags()?;
+        self.check_rustc_crate_type()?;
+        self.check_sysroot()?;
+        self.check_toolchain()?;
+
+        if self.check_linker {
+            Self::check_linker(PtxLinker)?;
+        }
+
+        if let Some(linker) = &self.linker {
+            Self::check_custom_linker(linker)?;
+        }
+
+        if self.emit_cubin {
+            Self::check_ptxas_support(&self.compute_capabilities)?;
+        }
+
+        self.prepare_lockfile()?;
+
+        // `core` for the target comes from elsewhere in these cases
+        if self.sysroot.is_none() && self.build_std.is_empty() {
+            self.check_target(Rustc)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Combines the outputs of every compute capability into a fatbin.
+    fn combine_outputs<'a>(
+        &'a self,
+        compute_capabilities: &[ComputeCapability],
+        mut outputs: Vec<BuildOutput<'a>>,
+    ) -> Result<BuildOutput<'a>> {
+        let fatbin_path = {
+            self.source_crate
+                .get_output_path(
+                    self.target_dir.as_deref(),
+                    &(self.get_build_config(None), compute_capabilities),
+                )
+                .context("Unable to create output path")?
+                .join(format!(
+                    "{}.fatbin",
+                    self.output_name
+                        .clone()
+                        .unwrap_or_else(|| self.get_output_file_prefix())
+                ))
+        };
+
+        self.combine_fatbin(compute_capabilities, &outputs, &fatbin_path)?;
+
+        if let Some(fatbin_dir) = fatbin_path.parent() {
+            self.touch_cached_build(fatbin_dir)?;
+        }
+
+        let was_cached = outputs.iter().all(BuildOutput::was_cached);
+        let build_duration = outputs.iter().map(BuildOutput::build_duration).sum();
+
+        let mut output = outputs.swap_remove(0);
+        output.fatbin_path = Some(fatbin_path);
+        output.was_cached = was_cached;
+        output.build_duration = build_duration;
+
+        Ok(output)
+    }
+
+    /// Builds every compute capability in its own thread, while the output
+    /// lines are forwarded from the calling thread.
+    ///
+    /// Results are in the order of `compute_capabilities`.
+    fn build_parallel<O: FnMut(&str), E: FnMut(&str)>(
+        &self,
+        compute_capabilities: &[ComputeCapability],
+        mut on_stdout_line: O,
+        mut on_stderr_line: E,
+    ) -> Vec<Result<BuildOutput<'_>>> {
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            let handles = compute_capabilities
+                .iter()
+                .map(|&compute_capability| {
+                    let stdout_sender = sender.clone();
+                    let stderr_sender = sender.clone();
+
+                    scope.spawn(move || {
+                        self.build_for(
+                            Some(compute_capability),
+                            |line| {
+                                stdout_sender.send(OutputLine::Stdout(line.into())).ok();
+                            },
+                            |line| {
+                                stderr_sender.send(OutputLine::Stderr(line.into())).ok();
+                            },
+                        )
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            drop(sender);
+
+            for line in receiver {
+                match line {
+                    OutputLine::Stdout(line) => on_stdout_line(&line),
+                    OutputLine::Stderr(line) => on_stderr_line(&line),
+                }
+            }
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|panic| resume_unwind(panic)))
+                .collect()
+        })
+    }
+
+    fn build_for<O: FnMut(&str), E: FnMut(&str)>(
+        &self,
+        compute_capability: Option<ComputeCapability>,
+        on_stdout_line: O,
+        mut on_stderr_line: E,
+    ) -> Result<BuildOutput<'_>> {
+        let (mut cargo, output_path) =
+            self.prepare_cargo(compute_capability, &mut on_stderr_line)?;
+        let listener = CargoListener::new(self, on_stdout_line, on_stderr_line);
+
+        let started_at = Instant::now();
+
+        let cargo_output = cargo.run_live_with_reset(
+            |line| listener.on_stdout_line(line),
+            |line| listener.on_stderr_line(line),
+            || listener.reset(),
+        );
+
+        self.complete_build(
+            compute_capability,
+            output_path,
+            cargo_output,
+            listener,
+            started_at.elapsed(),
+        )
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn build_for_async(
+        &self,
+        compute_capability: Option<ComputeCapability>,
+    ) -> Result<BuildOutput<'_>> {
+        let (mut cargo, output_path) = self.prepare_cargo(compute_capability, &mut |_line| ())?;
+        let listener = CargoListener::new(self, |_line| (), |_line| ());
+
+        let started_at = Instant::now();
+
+        let cargo_output = cargo
+            .run_live_async_with_reset(
+                |line| listener.on_stdout_line(line),
+                |line| listener.on_stderr_line(line),
+                || listener.reset(),
+            )
+            .await;
+
+        self.complete_build(
+            compute_capability,
+            output_path,
+            cargo_output,
+            listener,
+            started_at.elapsed(),
+        )
+    }
+
+    /// Creates the output path and the `cargo` command for the build.
+    fn prepare_cargo<E: FnMut(&str)>(
+        &self,
+        compute_capability: Option<ComputeCapability>,
+        on_stderr_line: &mut E,
+    ) -> Result<(ExecutableRunner<Cargo>, PathBuf)> {
+        let crate_type = self.get_crate_type()?;
+        let args = self.get_build_args(crate_type, compute_capability);
+
+        let output_path = {
+            self.source_crate
+                .get_output_path(
+                    self.target_dir.as_deref(),
+                    &self.get_build_config(compute_capability),
+                )
+                .context("Unable to create output path")?
+        };
+
+        if self.keep_intermediates {
+            on_stderr_line(&format!(
+                "note: keeping intermediate build files in `{}`",
+                output_path.display()
+            ));
+        }
+
+        self.touch_cached_build(&output_path)?;
+
+        let mut cargo = ExecutableRunner::new(Cargo);
+
+        for key in self.sanitized_env() {
+            cargo.without_env(key);
+        }
+
+        cargo
+            .with_envs(&self.env)
+            .with_retries(self.retries, RETRY_DELAY);
+
+        // The flags are passed in a single variable, that has precedence in `cargo`.
+        match self.get_env_rustflags() {
+            Some(rustflags) => cargo
+                .without_env("RUSTFLAGS")
+                .with_env("CARGO_ENCODED_RUSTFLAGS", rustflags.join("\x1f")),
+
+            None => cargo
+                .without_env("RUSTFLAGS")
+                .without_env("CARGO_ENCODED_RUSTFLAGS"),
+        };
+
+        if let Some(incremental) = self.get_incremental_env() {
+            cargo.with_env("CARGO_INCREMENTAL", incremental);
+        }
+
+        cargo
+            .with_args(&args)
+            .with_cwd(self.source_crate.get_path())
+            .with_env(&self.recursion_guard, "1")
+            .with_env("CARGO_TARGET_DIR", output_path.clone());
+
+        self.invalidate_outdated_build(&output_path)?;
+
+        self.notify_progress(BuildEvent::Started);
+
+        Ok((cargo, output_path))
+    }
+
+    /// Turns the finished `cargo` run into the build output.
+    fn complete_build<O: FnMut(&str), E: FnMut(&str)>(
+        &self,
+        compute_capability: Option<ComputeCapability>,
+        output_path: PathBuf,
+        cargo_output: Result<Output>,
+        listener: CargoListener<'_, O, E>,
+        build_duration: Duration,
+    ) -> Result<BuildOutput<'_>> {
+        let CargoListener {
+            diagnostics,
+            lines,
+            artifacts,
+            ..
+        } = listener;
+
+        let crate_type = self.get_crate_type()?;
+
+        let cargo_output = cargo_output.map_err(|error| match error.kind() {
+            BuildErrorKind::CommandFailed { .. } => Error::from(BuildErrorKind::BuildFailed {
+                diagnostics: diagnostics.take(),
+                lines: lines.into_inner(),
+                command: iter::once(String::from("cargo"))
+                    .chain(self.get_build_args(crate_type, compute_capability))
+                    .collect(),
+            }),
+            _ => error,
+        })?;
+
+        self.notify_progress(BuildEvent::Finished);
+        let artifacts = artifacts.into_inner();
+
+        let mut output =
+            self.prepare_output(output_path, &cargo_output.stderr, crate_type, &artifacts)?;
+
+        output.build_duration = build_duration;
+
+        output.warnings = diagnostics
+            .into_inner()
+            .into_iter()
+            .filter(|diagnostic| diagnostic.level == "warning")
+            .filter(|diagnostic| !Self::is_warnings_summary(&diagnostic.message))
+            .collect();
+
+        output.cargo_output = Some(Box::new(cargo_output));
+
+        self.finish_output(&mut output, compute_capability, &artifacts)?;
+
+        Ok(output)
+    }
+
+    /// Names the assembly and collects the details of the finished build.
+    fn finish_output(
+        &self,
+        output: &mut BuildOutput<'_>,
+        compute_capability: Option<ComputeCapability>,
+        artifacts: &[Artifact],
+    ) -> Result<()> {
+        let mut dependency_crates = artifacts
+            .iter()
+            .filter_map(Artifact::get_device_package)
+            .filter(|(name, _)| name != self.source_crate.get_name())
+            .collect::<Vec<_>>();
+
+        dependency_crates.sort();
+        dependency_crates.dedup();
+
+        output.dependency_crates = dependency_crates;
+
+        let postprocessor = match (&self.ptx_postprocessor, self.output_kind) {
+            (Some(PtxPostprocessor(postprocessor)), OutputKind::Ptx) => Some(postprocessor),
+            _ => None,
+        };
+
+        if self.output_name.is_some() || postprocessor.is_some() {
+            let assembly_path = output.get_assembly_path();
+
+            let final_path = match &self.output_name {
+                Some(output_name) => output.output_path.join(format!(
+                    "{output_name}.{}",
+                    self.output_kind.get_extension()
+                )),
+
+                None => output
+                    .output_path
+                    .join(assembly_path.file_name().unwrap_or_default()),
+            };
+
+            match postprocessor {
+                Some(postprocessor) => {
+                    let assembly =
+                        read_to_string(&assembly_path).context(BuildErrorKind::OtherError)?;
+
+                    write(&final_path, postprocessor(assembly)?)
+                        .context(BuildErrorKind::OtherError)?;
+                }
+
+                None => {
+                    copy(&assembly_path, &final_path).context(BuildErrorKind::OtherError)?;
+                }
+            }
+
+            output.assembly_path = Some(final_path);
+        }
+
+        let ptxas_args: &[&str] = if self.relocatable {
+            &["--compile-only"]
+        } else {
+            &[]
+        };
+
+        if self.validate_ptx && self.output_kind == OutputKind::Ptx {
+            Self::check_assembly(compute_capability, &output.get_assembly_path(), ptxas_args)?;
+        }
+
+        if self.require_kernels
+            && self.output_kind == OutputKind::Ptx
+            && output.kernel_names()?.is_empty()
+        {
+            bail!(BuildErrorKind::NoKernelsFound(output.get_assembly_path()));
+        }
+
+        // The hash is only a safeguard, so builds without a deps file are fine.
+        if let Ok(dependencies) = output.dependencies() {
+            Self::store_dependencies_hash(&output.output_path, &self.prefix, &dependencies)?;
+        }
+
+        if let Some(cubin_path) = output.get_cubin_path() {
+            Self::assemble_cubin(
+                compute_capability,
+                &output.get_assembly_path(),
+                &cubin_path,
+                ptxas_args,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Generates a missing `Cargo.lock`, unless a locked build was requested.
+    fn prepare_lockfile(&self) -> Result<()> {
+        if self.source_crate.get_lockfile_path().is_some() {
+            return Ok(());
+        }
+
+        let is_locked = self.frozen
+            || self
+                .cargo_args
+                .iter()
+                .any(|arg| arg == "--locked" || arg == "--frozen");
+
+        if is_locked {
+            bail!(BuildErrorKind::MissingLockfile(
+                self.source_crate.get_path().to_path_buf()
+            ));
+        }
+
+        let mut args = Vec::new();
+
+        if let Some(toolchain) = &self.toolchain {
+            args.push(format!("+{toolchain}"));
+        }
+
+        args.push(String::from("generate-lockfile"));
+
+        if self.offline {
+            args.push(String::from("--offline"));
+        }
+
+        let mut cargo = ExecutableRunner::new(Cargo);
+
+        for key in self.sanitized_env() {
+            cargo.without_env(key);
+        }
+
+        cargo
+            .with_args(&args)
+            .with_envs(&self.env)
+            .with_cwd(self.source_crate.get_path())
+            .with_retries(self.retries, RETRY_DELAY)
+            .run()?;
+
+        Ok(())
+    }
+
+    fn check_ptx_version(&self) -> Result<()> {
+        let Some(version) = self.ptx_version else {
+            return Ok(());
+        };
+
+        if PTX_VERSIONS.contains(&version) {
+            return Ok(());
+        }
+
+        bail!(BuildErrorKind::UnsupportedPtxVersion {
+            requested: format!("{}.{}", version.0, version.1),
+            supported: PTX_VERSIONS
+                .iter()
+                .map(|(major, minor)| format!("{major}.{minor}"))
+                .collect(),
+        })
+    }
+
+    fn check_unstable_flags(&self) -> Result<()> {
+        match self
+            .unstable_flags
+            .iter()
+            .find(|flag| !flag.starts_with("-Z"))
+        {
+            Some(flag) => bail!(BuildErrorKind::InvalidUnstableFlag(flag.clone())),
+            None => Ok(()),
+        }
+    }
+
+    fn check_rustc_crate_type(&self) -> Result<()> {
+        match &self.rustc_crate_type {
+            Some(crate_type) if !RUSTC_CRATE_TYPES.contains(&crate_type.as_str()) => {
+                bail!(BuildErrorKind::UnsupportedRustcCrateType {
+                    requested: crate_type.clone(),
+                    supported: RUSTC_CRATE_TYPES
+                        .iter()
+                        .copied()
+                        .map(String::from)
+                        .collect(),
+                })
+            }
+
+            _ => Ok(()),
+        }
+    }
+
+    fn check_sysroot(&self) -> Result<()> {
+        match &self.sysroot {
+            Some(sysroot) if !sysroot.is_dir() => {
+                bail!(BuildErrorKind::InvalidSysroot(sysroot.clone()))
+            }
+
+            _ => Ok(()),
+        }
+    }
+
+    fn check_toolchain(&self) -> Result<()> {
+        let Some(toolchain) = &self.toolchain else {
+            return Ok(());
+        };
+
+        ExecutableRunner::new(Cargo)
+            .with_args([format!("+{toolchain}"), String::from("-V")])
+            .run()
+            .map_err(|error| match error.kind() {
+                BuildErrorKind::CommandFailed { .. } => {
+                    Error::from(BuildErrorKind::ToolchainNotInstalled {
+                        toolchain: toolchain.clone(),
+                        hint: format!(
+                            "Please install it with `rustup toolchain install {toolchain}`"
+                        ),
+                    })
+                }
+                _ => error,
+            })?;
+
+        Ok(())
+    }
+
+    /// Checks the target in the sysroot of `rustc`, which is queried like
+    /// `cargo` runs it, e.g. with the `rust-toolchain` file of the crate.
+    fn check_target<Ex: Executable>(&self, rustc: Ex) -> Result<()> {
+        let mut runner = ExecutableRunner::new(rustc);
+
+        for key in self.sanitized_env() {
+            runner.without_env(key);
+        }
+
+        if let Some(toolchain) = &self.toolchain {
+            runner.with_args([format!("+{toolchain}")]);
+        }
+
+        let output = runner
+            .with_args(["--print", "sysroot"])
+            .with_envs(&self.env)
+            .with_cwd(self.source_crate.get_path())
+            .run()?;
+
+        Self::check_target_in_sysroot(Path::new(output.stdout.trim()), self.target)
+    }
+
+    /// Checks that the standard library of the `target` is installed in the
+    /// `sysroot`. `rustc --print target-list` doesn't help here, as it lists
+    /// every known target.
+    fn check_target_in_sysroot(sysroot: &Path, target: NvptxTarget) -> Result<()> {
+        let target = target.to_string();
+        let target_libs = sysroot
+            .join("lib")
+            .join("rustlib")
+            .join(&target)
+            .join("lib");
+
+        if !target_libs.is_dir() {
+            bail!(BuildErrorKind::TargetNotInstalled {
+                hint: format!("Please install it with `rustup target add {target}`"),
+                target,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn check_linker<Ex: Executable>(linker: Ex) -> Result<()> {
+        ExecutableRunner::new(linker).with_args(["-V"]).run()?;
+
+        Ok(())
+    }
+
+    /// Checks that the custom linker is a file or a command in `PATH`.
+    fn check_custom_linker(linker: &str) -> Result<()> {
+        let path = Path::new(linker);
+
+        let is_found = if path.components().count() > 1 {
+            path.is_file()
+        } else {
+            let paths = env::var_os("PATH").unwrap_or_default();
+
+            env::split_paths(&paths).any(|dir| {
+                dir.join(linker).is_file()
+                    || dir
+                        .join(format!("{linker}{}", env::consts::EXE_SUFFIX))
+                        .is_file()
+            })
+        };
+
+        if !is_found {
+            bail!(BuildErrorKind::CommandNotFound {
+                command: String::from(linker),
+                hint: String::from("Please check the path passed to `Builder::with_linker`"),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the installed `ptxas` can assemble for every compute
+    /// capability.
+    ///
+    /// Older `ptxas` without `--list-gpu-code` is not checked.
+    fn check_ptxas_support(compute_capabilities: &[ComputeCapability]) -> Result<()> {
+        if compute_capabilities.is_empty() {
+            return Ok(());
+        }
+
+        let mut ptxas = ExecutableRunner::new(Ptxas(CudaToolkit::locate()?));
+
+        let supported = match ptxas.with_args(["--list-gpu-code"]).run() {
+            Ok(output) => output
+                .stdout
+                .lines()
+                .map(str::trim)
+                .filter(|code| code.starts_with("sm_"))
+                .map(String::from)
+                .collect::<Vec<_>>(),
+
+            Err(error) if matches!(error.kind(), BuildErrorKind::CommandFailed { .. }) => {
+                return Ok(());
+            }
+
+            Err(error) => return Err(error),
+        };
+
+        for compute_capability in compute_capabilities {
+            let requested = compute_capability.to_string();
 
-                &json_format
+            if !supported.contains(&requested) {
+                bail!(BuildErrorKind::UnsupportedComputeCapability {
+                    requested,
+                    supported,
+                });
             }
-            MessageFormat::Short => "--message-format=short",
-        });
+        }
 
-        args.push("--target");
-        args.push(TARGET_NAME);
+        Ok(())
+    }
 
-        match self.crate_type {
-            Some(CrateType::Binary) => {
-                args.push("--bin");
-                args.push(self.source_crate.get_name());
-            }
+    fn check_assembly(
+        compute_capability: Option<ComputeCapability>,
+        assembly_path: &Path,
+        ptxas_args: &[&str],
+    ) -> Result<()> {
+        let result = Self::run_ptxas(
+            compute_capability,
+            assembly_path,
+            &assembly_path.with_extension("validation.cubin"),
+            ptxas_args,
+        );
 
-            Some(CrateType::Library) => {
-                args.push("--lib");
-            }
+        match result {
+            Ok(_) => Ok(()),
 
-            _ => {}
+            Err(error) => match error.kind() {
+                BuildErrorKind::CommandFailed { stderr, .. } => {
+                    bail!(BuildErrorKind::PtxValidationFailed {
+                        messages: stderr
+                            .lines()
+                            .filter(|line| !line.trim().is_empty())
+                            .map(String::from)
+                            .collect(),
+                    })
+                }
+
+                _ => Err(error),
+            },
         }
+    }
 
-        args.push("-v");
+    fn assemble_cubin(
+        compute_capability: Option<ComputeCapability>,
+        assembly_path: &Path,
+        cubin_path: &Path,
+        ptxas_args: &[&str],
+    ) -> Result<()> {
+        Self::run_ptxas(compute_capability, assembly_path, cubin_path, ptxas_args)?;
 
-        let crate_type = self.source_crate.get_crate_type(self.crate_type)?;
+        Ok(())
+    }
 
-        args.push("--");
+    fn run_ptxas(
+        compute_capability: Option<ComputeCapability>,
+        assembly_path: &Path,
+        cubin_path: &Path,
+        extra_args: &[&str],
+    ) -> Result<Output> {
+        let mut ptxas = ExecutableRunner::new(Ptxas(CudaToolkit::locate()?));
 
-        args.push("--crate-type");
-        args.push(crate_type);
+        if let Some(compute_capability) = compute_capability {
+            ptxas.with_args(["--gpu-name", &compute_capability.to_string()]);
+        }
 
-        let output_path = {
-            self.source_crate
-                .get_output_path()
-                .context("Unable to create output path")?
-        };
+        ptxas
+            .with_args(extra_args)
+            .with_args([OsStr::new("-o"), cubin_path.as_os_str()])
+            .with_args([assembly_path])
+            .run()
+    }
 
-        cargo
-            .with_args(&args)
-            .with_cwd(self.source_crate.get_path())
-            .with_env("PTX_CRATE_BUILDING", "1")
-            .with_env("CARGO_TARGET_DIR", output_path.clone());
+    fn combine_fatbin(
+        &self,
+        compute_capabilities: &[ComputeCapability],
+        outputs: &[BuildOutput],
+        fatbin_path: &Path,
+    ) -> Result<()> {
+        let mut fatbinary = ExecutableRunner::new(Fatbinary(CudaToolkit::locate()?));
 
-        for (key, val) in &self.env {
-            cargo.with_env(key, val);
-        }
+        fatbinary.with_args(["--64", &format!("--create={}", fatbin_path.display())]);
 
-        let cargo_output = cargo
-            .run_live(on_stdout_line, |line| {
-                if Self::output_is_not_verbose(line) {
-                    on_stderr_line(line);
-                }
-            })
-            .map_err(|error| match error.kind() {
-                BuildErrorKind::CommandFailed { stderr, .. } => {
-                    #[allow(clippy::manual_filter_map)]
-                    let lines = stderr
-                        .trim_matches('\n')
-                        .split('\n')
-                        .filter(|s| Self::output_is_not_verbose(s))
-                        .map(String::from)
-                        .collect();
+        for (compute_capability, output) in compute_capabilities.iter().zip(outputs) {
+            fatbinary.with_args([format!(
+                "--image=profile=compute_{},file={}",
+                compute_capability.get_sm_version(),
+                output.get_assembly_path().display()
+            )]);
 
-                    Error::from(BuildErrorKind::BuildFailed(lines))
-                }
-                _ => error,
-            })?;
+            if let Some(cubin_path) = output.get_cubin_path() {
+                fatbinary.with_args([format!(
+                    "--image=profile={},file={}",
+                    compute_capability,
+                    cubin_path.display()
+                )]);
+            }
+        }
 
-        Ok(BuildStatus::Success(self.prepare_output(
-            output_path,
-            &cargo_output.stderr,
-            crate_type,
-        )?))
+        fatbinary.with_cwd(self.source_crate.get_path()).run()?;
+
+        Ok(())
     }
 
     fn prepare_output(
@@ -371,12 +2669,28 @@ impl Builder {
         output_path: PathBuf,
         cargo_stderr: &str,
         crate_type: &str,
-    ) -> Result<BuildOutput> {
+        artifacts: &[Artifact],
+    ) -> Result<BuildOutput<'_>> {
         static SUFFIX_REGEX: LazyLock<Regex> = LazyLock::new(|| {
             Regex::new(r"-C extra-filename=([\S]+)").expect("Unable to parse regex...")
         });
 
-        let crate_name = self.source_crate.get_output_file_prefix();
+        let crate_name = &self.get_output_file_prefix();
+
+        let artifact = Artifact::find(artifacts, crate_name, crate_type);
+
+        // Cargo reports the emitted files, when the assembly is the final artifact.
+        if let Some(artifact) = artifact {
+            if let Some(assembly_path) = artifact.find_file(self.output_kind.get_extension()) {
+                if assembly_path.exists() {
+                    let mut output =
+                        BuildOutput::with_assembly_path(self, output_path, assembly_path.clone());
+
+                    output.was_cached = artifact.fresh;
+                    return Ok(output);
+                }
+            }
+        }
 
         // We need the build command to get real output filename.
         let build_command = {
@@ -406,7 +2720,12 @@ impl Builder {
             None => (String::new(), false),
         };
 
-        let output = BuildOutput::new(self, output_path, file_suffix);
+        let mut output = BuildOutput::new(self, output_path, file_suffix);
+
+        output.was_cached = match artifact {
+            Some(artifact) => artifact.fresh,
+            None => matches!(build_command, BuildCommand::Cached(_)),
+        };
 
         if output.get_assembly_path().exists() {
             Ok(output)
@@ -423,6 +2742,387 @@ impl Builder {
         }
     }
 
+    fn get_build_args(
+        &self,
+        crate_type: &str,
+        compute_capability: Option<ComputeCapability>,
+    ) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(toolchain) = &self.toolchain {
+            args.push(format!("+{toolchain}"));
+        }
+
+        args.push("rustc".into());
+
+        match &self.profile {
+            Profile::Debug => {}
+            Profile::Release => args.push("--release".into()),
+            Profile::Custom(name) => {
+                args.push("--profile".into());
+                args.push(name.clone());
+            }
+        }
+
+        args.push("--color".into());
+        args.push(if self.colors { "always" } else { "never" }.into());
+
+        args.push(self.get_message_format_arg());
+
+        if self.offline {
+            args.push("--offline".into());
+        }
+
+        if self.frozen {
+            args.push("--frozen".into());
+        }
+
+        if let Some(jobs) = self.jobs {
+            args.push("--jobs".into());
+            args.push(jobs.to_string());
+        }
+
+        for config in &self.cargo_config {
+            args.push("--config".into());
+            args.push(config.clone());
+        }
+
+        if !self.build_std.is_empty() {
+            args.push("-Z".into());
+            args.push(format!("build-std={}", self.build_std.join(",")));
+        }
+
+        if !self.build_std_features.is_empty() {
+            args.push("-Z".into());
+            args.push(format!(
+                "build-std-features={}",
+                self.build_std_features.join(",")
+            ));
+        }
+
+        if let Some(package) = &self.package {
+            args.push("--package".into());
+            args.push(package.clone());
+        }
+
+        if self.source_crate.has_custom_manifest() {
+            args.push("--manifest-path".into());
+            args.push(
+                self.source_crate
+                    .get_manifest_path()
+                    .to_string_lossy()
+                    .into_owned(),
+            );
+        }
+
+        args.push("--target".into());
+        args.push(self.target.to_string());
+
+        if self.all_features {
+            args.push("--all-features".into());
+        } else if !self.features.is_empty() {
+            args.push("--features".into());
+            args.push(self.features.join(","));
+        }
+
+        if !self.default_features {
+            args.push("--no-default-features".into());
+        }
+
+        match (&self.bin, self.crate_type) {
+            _ if self.example.is_some() => {
+                args.push("--example".into());
+                args.extend(self.example.clone());
+            }
+
+            (Some(bin), _) => {
+                args.push("--bin".into());
+                args.push(bin.clone());
+            }
+
+            (None, Some(CrateType::Binary)) => {
+                args.push("--bin".into());
+                args.push(self.source_crate.get_name().into());
+            }
+
+            (None, Some(CrateType::Library)) => {
+                args.push("--lib".into());
+            }
+
+            _ => {}
+        }
+
+        args.push(
+            match self.verbosity {
+                Verbosity::Quiet => "-q",
+                Verbosity::Normal => "-v",
+                Verbosity::Verbose => "-vv",
+            }
+            .into(),
+        );
+
+        args.extend(self.cargo_args.iter().cloned());
+
+        args.push("--".into());
+
+        args.extend(self.get_rustc_args(crate_type, compute_capability));
+
+        args
+    }
+
+    fn get_message_format_arg(&self) -> String {
+        match self.message_format {
+            // Diagnostics are parsed from the JSON messages and printed in a
+            // human-readable form afterwards.
+            MessageFormat::Human if self.colors => {
+                "--message-format=json,json-diagnostic-rendered-ansi".into()
+            }
+            MessageFormat::Human => "--message-format=json".into(),
+            MessageFormat::Json {
+                render_diagnostics,
+                short,
+                ansi,
+            } => {
+                let mut json_format = String::from("--message-format=json");
+
+                if render_diagnostics {
+                    json_format.push_str(",json-render-diagnostics");
+                }
+
+                if short {
+                    json_format.push_str(",json-diagnostic-short");
+                }
+
+                if ansi {
+                    json_format.push_str(",json-diagnostic-rendered-ansi");
+                }
+
+                json_format
+            }
+            MessageFormat::Short if self.colors => {
+                "--message-format=json,json-diagnostic-short,json-diagnostic-rendered-ansi".into()
+            }
+            MessageFormat::Short => "--message-format=json,json-diagnostic-short".into(),
+        }
+    }
+
+    fn get_rustc_args(
+        &self,
+        crate_type: &str,
+        compute_capability: Option<ComputeCapability>,
+    ) -> Vec<String> {
+        let mut args = Vec::new();
+
+        args.push("--crate-type".into());
+        args.push(crate_type.into());
+
+        match self.output_kind {
+            OutputKind::Ptx => (),
+            OutputKind::LlvmIr => args.push("--emit=llvm-ir".into()),
+            OutputKind::LlvmBc => args.push("--emit=llvm-bc".into()),
+        }
+
+        if self.deny_warnings {
+            args.push("-D".into());
+            args.push("warnings".into());
+        }
+
+        if self.keep_intermediates {
+            args.push("-C".into());
+            args.push("save-temps".into());
+        }
+
+        if let Some(opt_level) = self.opt_level {
+            args.push("-C".into());
+            args.push(format!("opt-level={opt_level}"));
+        }
+
+        if let Some(lto) = self.lto {
+            args.push("-C".into());
+            args.push(format!("lto={lto}"));
+        }
+
+        if let Some(panic_strategy) = self.panic_strategy {
+            args.push("-C".into());
+            args.push(format!("panic={panic_strategy}"));
+        }
+
+        if let Some(debug_info) = self.debug_info {
+            args.push("-C".into());
+            args.push(format!("debuginfo={debug_info}"));
+        }
+
+        if let Some(codegen_units) = self.codegen_units {
+            args.push("-C".into());
+            args.push(format!("codegen-units={codegen_units}"));
+        }
+
+        if let Some(overflow_checks) = self.overflow_checks {
+            args.push("-C".into());
+            args.push(format!(
+                "overflow-checks={}",
+                if overflow_checks { "on" } else { "off" }
+            ));
+        }
+
+        if let Some((major, minor)) = self.ptx_version {
+            args.push("-C".into());
+            args.push(format!("target-feature=+ptx{major}{minor}"));
+        }
+
+        if let Some(compute_capability) = compute_capability {
+            args.push("-C".into());
+            args.push(format!("target-cpu={compute_capability}"));
+        }
+
+        if let Some(linker) = &self.linker {
+            args.push("-C".into());
+            args.push(format!("linker={linker}"));
+        }
+
+        if let Some(sysroot) = &self.sysroot {
+            args.push("--sysroot".into());
+            args.push(sysroot.display().to_string());
+        }
+
+        args.extend(
+            self.unstable_flags
+                .iter()
+                .flat_map(|flag| flag.split_whitespace().map(String::from)),
+        );
+
+        args.extend(self.rustc_flags.iter().cloned());
+
+        args
+    }
+
+    fn get_build_config(&self, compute_capability: Option<ComputeCapability>) -> impl Hash + '_ {
+        (
+            self.target,
+            self.output_kind,
+            (
+                self.opt_level,
+                self.lto,
+                self.panic_strategy,
+                self.debug_info,
+                self.codegen_units,
+                self.overflow_checks,
+                self.ptx_version,
+            ),
+            compute_capability,
+            (
+                &self.rustc_flags,
+                &self.unstable_flags,
+                (&self.toolchain, &self.sysroot, &self.linker),
+                &self.rustc_crate_type,
+                &self.cargo_args,
+                &self.cargo_config,
+                (&self.build_std, &self.build_std_features),
+                self.deny_warnings,
+                self.keep_intermediates,
+                self.relocatable,
+            ),
+            &self.features,
+            self.default_features,
+            self.all_features,
+            &self.bin,
+            &self.example,
+            &self.env,
+        )
+    }
+
+    fn get_incremental_env(&self) -> Option<&'static str> {
+        self.incremental.map(|on| if on { "1" } else { "0" })
+    }
+
+    /// Returns the flags of `CARGO_ENCODED_RUSTFLAGS` or `RUSTFLAGS` from the
+    /// builder or the process environment, in the precedence of `cargo`.
+    fn get_env_rustflags(&self) -> Option<Vec<String>> {
+        let get_var = |key: &str| {
+            self.env
+                .get(OsStr::new(key))
+                .cloned()
+                .or_else(|| env::var_os(key))
+                .map(|value| value.to_string_lossy().into_owned())
+        };
+
+        let rustflags = match get_var("CARGO_ENCODED_RUSTFLAGS") {
+            Some(encoded) => encoded
+                .split('\x1f')
+                .filter(|flag| !flag.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>(),
+
+            None => get_var("RUSTFLAGS")
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(String::from)
+                .collect(),
+        };
+
+        if rustflags.is_empty() {
+            None
+        } else {
+            Some(rustflags)
+        }
+    }
+
+    fn get_crate_type(&self) -> Result<&str> {
+        let crate_type = match &self.example {
+            Some(example) => self.source_crate.get_example_crate_type(example)?,
+            None => self
+                .source_crate
+                .get_crate_type(self.crate_type, self.bin.as_deref())?,
+        };
+
+        Ok(self.rustc_crate_type.as_deref().unwrap_or(crate_type))
+    }
+
+    fn get_output_file_prefix(&self) -> String {
+        match (&self.example, &self.bin) {
+            (Some(name), _) | (None, Some(name)) => name.replace('-', "_"),
+            (None, None) => self.source_crate.get_output_file_prefix().to_string(),
+        }
+    }
+
+    /// Returns the directory of the final artifacts inside of the profile
+    /// directory.
+    fn get_artifacts_dir(&self) -> &str {
+        if self.example.is_some() {
+            "examples"
+        } else {
+            "deps"
+        }
+    }
+
+    fn notify_progress(&self, event: BuildEvent) {
+        if let Some(ProgressCallback(callback)) = &self.progress {
+            callback(event);
+        }
+    }
+
+    /// Checks for the `N warnings emitted` summary, that isn't a warning itself.
+    fn is_warnings_summary(message: &str) -> bool {
+        static SUMMARY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^\d+ warnings? emitted$").expect("Unable to parse regex...")
+        });
+
+        SUMMARY_REGEX.is_match(message)
+    }
+
+    fn parse_compiling_crate(line: &str) -> Option<String> {
+        static COMPILING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^\s*Compiling\s+(\S+)\s").expect("Unable to parse regex...")
+        });
+
+        static ANSI_REGEX: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").expect("Unable to parse regex..."));
+
+        COMPILING_REGEX
+            .captures(&ANSI_REGEX.replace_all(line, ""))
+            .map(|caps| caps[1].to_string())
+    }
+
     fn output_is_not_verbose(line: &str) -> bool {
         !line.starts_with("+ ")
             && !line.contains("Running")
@@ -431,11 +3131,185 @@ impl Builder {
             && !line.starts_with("  process didn\'t exit successfully: ")
     }
 
-    fn load_cached_build_command(output_path: &Path, prefix: &str) -> Option<BuildCommand> {
-        match read_to_string(output_path.join(format!("{LAST_BUILD_CMD}.{prefix}"))) {
-            Ok(contents) => Some(BuildCommand::Cached(contents)),
-            Err(_) => None,
+    fn is_kept_output_line(&self, line: &str) -> bool {
+        match &self.diagnostic_filter {
+            Some(DiagnosticFilter(filter)) => filter(line),
+            None => !NOISE_LINES
+                .iter()
+                .any(|noise| line.trim_start().starts_with(noise)),
+        }
+    }
+
+    fn load_cached_build_command(output_path: &Path, prefix: &str) -> Option<BuildCommand> {
+        match read_to_string(output_path.join(format!("{LAST_BUILD_CMD}.{prefix}"))) {
+            Ok(contents) => Some(BuildCommand::Cached(contents)),
+            Err(_) => None,
+        }
+    }
+
+    /// Marks the cached build at `output_path` as recently used, when the
+    /// cache is limited.
+    ///
+    /// A marker file is rewritten, as directories can't be opened to set
+    /// their mtime on Windows.
+    fn touch_cached_build(&self, output_path: &Path) -> Result<()> {
+        if self.cache_limit.is_none() {
+            return Ok(());
+        }
+
+        write(output_path.join(LAST_USED), []).context(BuildErrorKind::OtherError)
+    }
+
+    /// Removes the least recently used cached builds beyond the limit.
+    ///
+    /// The entries of the current build, e.g. with kept intermediates, are
+    /// left in place.
+    fn evict_cached_builds(&self) -> Result<()> {
+        let Some(cache_limit) = self.cache_limit else {
+            return Ok(());
+        };
+
+        let current_builds = self.current_cached_builds();
+
+        let mut cached_builds = self
+            .cached_builds()?
+            .into_iter()
+            .filter(|path| !current_builds.contains(path))
+            .map(|path| {
+                let last_used = path
+                    .join(LAST_USED)
+                    .metadata()
+                    .and_then(|meta| meta.modified());
+
+                (last_used.ok(), path)
+            })
+            .collect::<Vec<_>>();
+
+        // Most recently used first.
+        cached_builds.sort_by_key(|(modified, _)| Reverse(*modified));
+
+        let remaining_limit = cache_limit.saturating_sub(current_builds.len());
+
+        for (_, path) in cached_builds.into_iter().skip(remaining_limit) {
+            remove_dir_all(path).context(BuildErrorKind::OtherError)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the cached build directories, that the current settings use.
+    fn current_cached_builds(&self) -> Vec<PathBuf> {
+        let target_dir = self.target_dir.as_deref();
+
+        let mut current_builds = match self.compute_capabilities.as_slice() {
+            [] => vec![self
+                .source_crate
+                .get_output_location(target_dir, &self.get_build_config(None))],
+            compute_capabilities => compute_capabilities
+                .iter()
+                .map(|compute_capability| {
+                    self.source_crate.get_output_location(
+                        target_dir,
+                        &self.get_build_config(Some(*compute_capability)),
+                    )
+                })
+                .collect(),
+        };
+
+        if self.compute_capabilities.len() > 1 {
+            current_builds.push(self.source_crate.get_output_location(
+                target_dir,
+                &(
+                    self.get_build_config(None),
+                    self.compute_capabilities.as_slice(),
+                ),
+            ));
+        }
+
+        current_builds
+    }
+
+    /// Forces `cargo` to rebuild the crate, when contents of its sources
+    /// changed since the last build, even if their mtimes did not.
+    fn invalidate_outdated_build(&self, output_path: &Path) -> Result<()> {
+        let Ok(contents) =
+            read_to_string(output_path.join(format!("{DEPENDENCIES_HASH}.{}", self.prefix)))
+        else {
+            return Ok(());
+        };
+
+        let mut lines = contents.lines();
+        let stored_hash = lines.next().unwrap_or_default();
+        let dependencies = lines.map(PathBuf::from).collect::<Vec<_>>();
+
+        if Self::hash_dependencies(&dependencies) == stored_hash {
+            return Ok(());
+        }
+
+        let fingerprint_path = output_path
+            .join(self.target.to_string())
+            .join(self.profile.get_dir_name())
+            .join(".fingerprint");
+
+        let fingerprint_prefix = format!("{}-", self.source_crate.get_name());
+
+        let Ok(entries) = read_dir(fingerprint_path) else {
+            return Ok(());
+        };
+
+        for entry in entries {
+            let entry = entry.context(BuildErrorKind::OtherError)?;
+
+            let is_crate_fingerprint = entry
+                .file_name()
+                .to_string_lossy()
+                .strip_prefix(&fingerprint_prefix)
+                .is_some_and(|hash| !hash.contains('-'));
+
+            if is_crate_fingerprint {
+                remove_dir_all(entry.path()).context(BuildErrorKind::OtherError)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn store_dependencies_hash(
+        output_path: &Path,
+        prefix: &str,
+        dependencies: &[PathBuf],
+    ) -> Result<()> {
+        let mut contents = Self::hash_dependencies(dependencies);
+
+        for path in dependencies {
+            contents.push('\n');
+            contents.push_str(&path.to_string_lossy());
+        }
+
+        write(
+            output_path.join(format!("{DEPENDENCIES_HASH}.{prefix}")),
+            contents,
+        )
+        .context(BuildErrorKind::OtherError)
+    }
+
+    fn hash_dependencies(dependencies: &[PathBuf]) -> String {
+        let mut hasher = StableHasher::new();
+
+        for path in dependencies {
+            hasher.write_path(path);
+
+            match read(path) {
+                Ok(contents) => {
+                    hasher.write_u8(1);
+                    hasher.write_bytes(&contents);
+                }
+
+                Err(_) => hasher.write_u8(0),
+            }
         }
+
+        format!("{:x}", hasher.finish())
     }
 
     fn store_cached_build_command(output_path: &Path, prefix: &str, command: &str) -> Result<()> {
@@ -449,17 +3323,98 @@ impl Builder {
     }
 }
 
+impl<'a> BuildStatus<'a> {
+    /// Returns `true` for [`BuildStatus::Success`](#variant.Success).
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        matches!(self, BuildStatus::Success(_))
+    }
+
+    /// Returns `true` for [`BuildStatus::NotNeeded`](#variant.NotNeeded).
+    #[must_use]
+    pub fn is_not_needed(&self) -> bool {
+        matches!(self, BuildStatus::NotNeeded)
+    }
+
+    /// Returns the output of a successful build.
+    #[must_use]
+    pub fn into_output(self) -> Option<BuildOutput<'a>> {
+        match self {
+            BuildStatus::Success(output) => Some(output),
+            BuildStatus::NotNeeded => None,
+        }
+    }
+
+    /// Returns the output of a successful build.
+    ///
+    /// # Panics
+    /// Panics, when the build was not needed, e.g. in a recursive `build.rs`
+    /// call.
+    ///
+    /// # Usage
+    /// ``` no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let builder = Builder::new(".")?;
+    /// let output = builder.build()?.unwrap_output();
+    ///
+    /// println!("{}", output.get_assembly_path().display());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn unwrap_output(self) -> BuildOutput<'a> {
+        match self {
+            BuildStatus::Success(output) => output,
+            BuildStatus::NotNeeded => {
+                panic!("called `BuildStatus::unwrap_output()` on a `NotNeeded` value")
+            }
+        }
+    }
+}
+
 impl<'a> BuildOutput<'a> {
     fn new(builder: &'a Builder, output_path: PathBuf, file_suffix: String) -> Self {
         BuildOutput {
             builder,
             output_path,
             file_suffix,
+            assembly_path: None,
+            fatbin_path: None,
+            was_cached: false,
+            build_duration: Duration::ZERO,
+            dependency_crates: Vec::new(),
+            warnings: Vec::new(),
+            cargo_output: None,
+        }
+    }
+
+    fn with_assembly_path(
+        builder: &'a Builder,
+        output_path: PathBuf,
+        assembly_path: PathBuf,
+    ) -> Self {
+        BuildOutput {
+            builder,
+            output_path,
+            file_suffix: String::new(),
+            assembly_path: Some(assembly_path),
+            fatbin_path: None,
+            was_cached: false,
+            build_duration: Duration::ZERO,
+            dependency_crates: Vec::new(),
+            warnings: Vec::new(),
+            cargo_output: None,
         }
     }
 
     /// Returns path to PTX assembly file.
     ///
+    /// Points to the LLVM-IR or bitcode file instead, if another
+    /// [`OutputKind`](enum.OutputKind.html) was requested.
+    ///
     /// # Usage
     /// Can be used from `build.rs` script to provide Rust with the path
     /// via environment variable:
@@ -479,17 +3434,388 @@ impl<'a> BuildOutput<'a> {
     /// ```
     #[must_use]
     pub fn get_assembly_path(&self) -> PathBuf {
+        if let Some(assembly_path) = &self.assembly_path {
+            return assembly_path.clone();
+        }
+
         self.output_path
-            .join(TARGET_NAME)
-            .join(self.builder.profile.to_string())
-            .join("deps")
+            .join(self.builder.target.to_string())
+            .join(self.builder.profile.get_dir_name())
+            .join(self.builder.get_artifacts_dir())
             .join(format!(
-                "{}{}.ptx",
-                self.builder.source_crate.get_output_file_prefix(),
+                "{}{}.{}",
+                self.builder.get_output_file_prefix(),
                 self.file_suffix,
+                self.builder.output_kind.get_extension(),
             ))
     }
 
+    /// Returns the name of the built crate.
+    #[must_use]
+    pub fn crate_name(&self) -> &str {
+        self.builder.source_crate.get_name()
+    }
+
+    /// Returns the version of the built crate.
+    #[must_use]
+    pub fn crate_version(&self) -> &Version {
+        self.builder.source_crate.get_version()
+    }
+
+    /// Returns `true` if the crate was not recompiled, because the cached
+    /// build was up-to-date.
+    #[must_use]
+    pub fn was_cached(&self) -> bool {
+        self.was_cached
+    }
+
+    /// Returns how long `cargo` took to build the crate.
+    ///
+    /// Covers the builds for all compute capabilities, if several were
+    /// requested.
+    #[must_use]
+    pub fn build_duration(&self) -> Duration {
+        self.build_duration
+    }
+
+    /// Returns the full standard output of `cargo`.
+    ///
+    /// Consists of the JSON messages, unless the crate was built for several
+    /// compute capabilities: then only the first build is covered.
+    #[must_use]
+    pub fn raw_cargo_stdout(&self) -> &str {
+        self.cargo_output
+            .as_ref()
+            .map_or("", |output| output.stdout.as_str())
+    }
+
+    /// Returns the full standard error output of `cargo`, e.g. the
+    /// `Compiling` and `Finished` lines.
+    ///
+    /// Only the first build is covered, if the crate was built for several
+    /// compute capabilities.
+    #[must_use]
+    pub fn raw_cargo_stderr(&self) -> &str {
+        self.cargo_output
+            .as_ref()
+            .map_or("", |output| output.stderr.as_str())
+    }
+
+    /// Returns the warnings `rustc` reported for the crate.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     assert!(output.warnings().len() <= 10, "too many warnings");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn warnings(&self) -> &[Diagnostic] {
+        &self.warnings
+    }
+
+    /// Returns path to the cubin file, if it was requested with
+    /// [`Builder::emit_cubin`](struct.Builder.html#method.emit_cubin).
+    #[must_use]
+    pub fn get_cubin_path(&self) -> Option<PathBuf> {
+        if self.builder.emit_cubin {
+            Some(self.get_assembly_path().with_extension("cubin"))
+        } else {
+            None
+        }
+    }
+
+    /// Returns path to the fat binary, if several compute capabilities were
+    /// requested with
+    /// [`Builder::set_compute_capabilities`](struct.Builder.html#method.set_compute_capabilities).
+    #[must_use]
+    pub fn get_fatbin_path(&self) -> Option<&Path> {
+        self.fatbin_path.as_deref()
+    }
+
+    /// Returns contents of the PTX assembly file.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     let ptx = output.get_assembly_string()?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_assembly_string(&self) -> Result<String> {
+        read_to_string(self.get_assembly_path()).context(BuildErrorKind::OtherError)
+    }
+
+    /// Returns raw contents of the PTX assembly file, e.g. to embed them as
+    /// a byte array.
+    pub fn get_assembly_bytes(&self) -> Result<Vec<u8>> {
+        read(self.get_assembly_path()).context(BuildErrorKind::OtherError)
+    }
+
+    /// Returns the SHA-256 digest of the PTX assembly file as a lowercase hex
+    /// string.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     println!("cargo:rustc-env=KERNELS_SHA256={}", output.assembly_sha256()?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn assembly_sha256(&self) -> Result<String> {
+        Ok(format!("{:x}", Sha256::digest(self.get_assembly_bytes()?)))
+    }
+
+    /// Returns names of the kernels in the PTX assembly, in source order.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     for name in output.kernel_names()? {
+    ///         println!("found kernel: {name}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn kernel_names(&self) -> Result<Vec<String>> {
+        Ok(ptx::kernel_names(&self.get_assembly_string()?))
+    }
+
+    /// Returns signatures of the kernels in the PTX assembly, in source order.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     for signature in output.kernel_signatures()? {
+    ///         println!("{}: {} params", signature.name, signature.params.len());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn kernel_signatures(&self) -> Result<Vec<KernelSignature>> {
+        Ok(ptx::kernel_signatures(&self.get_assembly_string()?))
+    }
+
+    /// Returns names and visibilities of the kernels in the PTX assembly, in
+    /// source order.
+    ///
+    /// Device functions (`.func`) are not listed.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     for (name, visibility) in output.kernels_with_visibility()? {
+    ///         if visibility == KernelVisibility::Visible {
+    ///             println!("exported kernel: {name}");
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn kernels_with_visibility(&self) -> Result<Vec<(String, KernelVisibility)>> {
+        Ok(ptx::kernels_with_visibility(&self.get_assembly_string()?))
+    }
+
+    /// Links the PTX assembly with the assemblies of `others` into a single
+    /// module, next to the own assembly, and returns its path.
+    ///
+    /// See [`link_ptx`](fn.link_ptx.html) for the requirements.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let kernels = Builder::new("kernels")?;
+    /// let helpers = Builder::new("helpers")?;
+    ///
+    /// if let (BuildStatus::Success(kernels), BuildStatus::Success(helpers)) =
+    ///     (kernels.build()?, helpers.build()?)
+    /// {
+    ///     println!("linked: {}", kernels.link_with(&[&helpers])?.display());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn link_with(&self, others: &[&BuildOutput]) -> Result<PathBuf> {
+        let assembly_path = self.get_assembly_path();
+        let linked_path = assembly_path.with_extension("linked.ptx");
+
+        let mut modules = vec![assembly_path];
+        modules.extend(others.iter().map(|other| other.get_assembly_path()));
+
+        link_ptx(&modules, &linked_path)?;
+
+        Ok(linked_path)
+    }
+
+    /// Writes a Rust module with the PTX assembly as `PTX` constant and the
+    /// kernel names as `KERNELS` constant.
+    ///
+    /// # Usage
+    /// Can be used from `build.rs` script to embed the PTX into the host
+    /// crate:
+    /// ```no_run
+    /// use std::{env, path::Path};
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     let out_dir = env::var("OUT_DIR").unwrap();
+    ///     output.write_rust_module(&Path::new(&out_dir).join("kernels.rs"))?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// The module can be included with:
+    /// ```ignore
+    /// mod kernels {
+    ///     include!(concat!(env!("OUT_DIR"), "/kernels.rs"));
+    /// }
+    /// ```
+    pub fn write_rust_module(&self, path: &Path) -> Result<()> {
+        let module = ptx::rust_module(&self.get_assembly_string()?);
+
+        write(path, module).context(BuildErrorKind::OtherError)
+    }
+
+    /// Copies the PTX assembly to `dest` and returns the path of the copy.
+    ///
+    /// Missing parent directories are created and an existing file is
+    /// overwritten. When `dest` is a directory, the assembly keeps its file
+    /// name.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use std::{env, path::Path};
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new("kernels")?.build()? {
+    ///     let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    ///     output.copy_assembly_to(&Path::new(&manifest_dir).join("kernels").join("foo.ptx"))?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn copy_assembly_to(&self, dest: &Path) -> Result<PathBuf> {
+        let assembly_path = self.get_assembly_path();
+
+        let dest = match assembly_path.file_name() {
+            Some(file_name) if dest.is_dir() => dest.join(file_name),
+            _ => dest.to_path_buf(),
+        };
+
+        if let Some(parent) = dest.parent() {
+            create_dir_all(parent).context(BuildErrorKind::OtherError)?;
+        }
+
+        copy(&assembly_path, &dest).context(BuildErrorKind::OtherError)?;
+
+        Ok(dest)
+    }
+
+    /// Returns a unified diff of the `baseline` PTX file against the
+    /// assembly, or `None` if they are identical.
+    ///
+    /// Comment lines, like the generator header, are ignored, so that
+    /// "golden" PTX files only change with the emitted code.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     if let Some(diff) = output.diff_assembly(Path::new("golden/kernels.ptx"))? {
+    ///         panic!("PTX codegen changed:\n{diff}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff_assembly(&self, baseline: &Path) -> Result<Option<String>> {
+        let baseline_assembly = read_to_string(baseline).context(BuildErrorKind::OtherError)?;
+
+        Ok(ptx::diff(
+            &baseline_assembly,
+            &self.get_assembly_string()?,
+            (
+                &baseline.display().to_string(),
+                &self.get_assembly_path().display().to_string(),
+            ),
+        ))
+    }
+
+    /// Returns register and memory usage of all kernels.
+    ///
+    /// Assembles the PTX with `ptxas -v` for the (first) requested compute
+    /// capability, so the CUDA toolkit has to be installed.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     for usage in output.resource_usage()? {
+    ///         println!("{}: {} registers", usage.name, usage.registers);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resource_usage(&self) -> Result<Vec<KernelResourceUsage>> {
+        let assembly_path = self.get_assembly_path();
+
+        let ptxas_output = Builder::run_ptxas(
+            self.builder.compute_capabilities.first().copied(),
+            &assembly_path,
+            &assembly_path.with_extension("resource-usage.cubin"),
+            &["-v"],
+        )?;
+
+        Ok(ptx::resource_usage(&ptxas_output.stderr))
+    }
+
     /// Returns a list of crate dependencies.
     ///
     /// # Usage
@@ -527,21 +3853,15 @@ impl<'a> BuildOutput<'a> {
             .skip(1)
             .collect::<String>();
 
-        let mut cargo_lock_dir = self.builder.source_crate.get_path();
-
-        // Traverse the workspace directory structure towards the root
-        while !cargo_lock_dir.join("Cargo.lock").is_file() {
-            cargo_lock_dir = match cargo_lock_dir.parent() {
-                Some(parent) => parent,
-                None => bail!(BuildErrorKind::InternalError(String::from(
-                    "Unable to find Cargo.lock file",
-                ))),
-            }
-        }
+        let Some(cargo_lock_path) = self.builder.source_crate.get_lockfile_path() else {
+            bail!(BuildErrorKind::InternalError(String::from(
+                "Unable to find Cargo.lock file",
+            )));
+        };
 
         let cargo_deps = vec![
-            self.builder.source_crate.get_path().join("Cargo.toml"),
-            cargo_lock_dir.join("Cargo.lock"),
+            self.builder.source_crate.get_manifest_path().to_path_buf(),
+            cargo_lock_path,
         ];
 
         Ok(deps_contents
@@ -552,17 +3872,146 @@ impl<'a> BuildOutput<'a> {
             .collect())
     }
 
+    /// Returns the build metadata, that can outlive the builder.
+    ///
+    /// # Usage
+    /// With `serde` feature enabled, the metadata can be persisted:
+    /// ```ignore
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     let metadata = serde_json::to_string(&output.metadata()?).unwrap();
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn metadata(&self) -> Result<BuildMetadata> {
+        Ok(BuildMetadata {
+            assembly_path: self.get_assembly_path(),
+            dependencies: self.dependencies()?,
+            kernels: self.kernel_names()?,
+        })
+    }
+
+    /// Returns the summary of the build.
+    pub fn report(&self) -> Result<BuildReport> {
+        Ok(BuildReport {
+            crate_name: self.crate_name().to_string(),
+            crate_version: self.crate_version().to_string(),
+            profile: self.builder.profile.to_string(),
+            target: self.builder.target.to_string(),
+            compute_capabilities: self
+                .builder
+                .compute_capabilities
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            assembly_path: self.get_assembly_path(),
+            kernels: self.kernel_names()?,
+            dependencies: self.dependencies()?,
+            was_cached: self.was_cached,
+            build_duration: self.build_duration,
+        })
+    }
+
+    /// Writes the [`report`](#method.report) of the build as JSON to `path`.
+    ///
+    /// # Usage
+    /// ```ignore
+    /// use std::path::Path;
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     output.write_report(Path::new("target/ptx-report.json"))?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn write_report(&self, path: &Path) -> Result<()> {
+        let report =
+            serde_json::to_string_pretty(&self.report()?).context(BuildErrorKind::OtherError)?;
+
+        write(path, report).context(BuildErrorKind::OtherError)
+    }
+
+    /// Returns names and versions of the dependency crates, that were built
+    /// for the NVPTX target.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     for (name, version) in output.dependency_crates()? {
+    ///         println!("{name} {version}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dependency_crates(&self) -> Result<Vec<(String, Version)>> {
+        Ok(self.dependency_crates.clone())
+    }
+
+    /// Returns `cargo:rerun-if-changed` directives for every crate dependency
+    /// and the produced PTX assembly.
+    pub fn rerun_directives(&self) -> Result<Vec<String>> {
+        Ok(self
+            .dependencies()?
+            .into_iter()
+            .chain([self.get_assembly_path()])
+            .map(|path| format!("cargo:rerun-if-changed={}", path.display()))
+            .collect())
+    }
+
+    /// Prints `cargo:rerun-if-changed` directives for every crate dependency
+    /// and the produced PTX assembly.
+    ///
+    /// # Usage
+    /// Can be used from `build.rs` script to notify Cargo the dependencies,
+    /// so it can automatically rebuild on changes:
+    /// ```no_run
+    /// use ptx_builder::prelude::*;
+    /// # use ptx_builder::error::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// if let BuildStatus::Success(output) = Builder::new(".")?.build()? {
+    ///     output.emit_rerun_directives()?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn emit_rerun_directives(&self) -> Result<()> {
+        for directive in self.rerun_directives()? {
+            println!("{directive}");
+        }
+
+        Ok(())
+    }
+
     fn get_deps_file_contents(&self) -> Result<String> {
-        let crate_deps_path = self
+        let profile_path = self
             .output_path
-            .join(TARGET_NAME)
-            .join(self.builder.profile.to_string())
-            .join(format!(
+            .join(self.builder.target.to_string())
+            .join(self.builder.profile.get_dir_name());
+
+        let crate_deps_path = match (&self.builder.example, &self.builder.bin) {
+            (Some(example), _) => profile_path.join("examples").join(format!("{example}.d")),
+            (None, Some(bin)) => profile_path.join(format!("{bin}.d")),
+            (None, None) => profile_path.join(format!(
                 "{}.d",
                 self.builder
                     .source_crate
                     .get_deps_file_prefix(self.builder.crate_type)?
-            ));
+            )),
+        };
 
         let mut crate_deps_reader =
             BufReader::new(File::open(crate_deps_path).context(BuildErrorKind::OtherError)?);
@@ -577,12 +4026,281 @@ impl<'a> BuildOutput<'a> {
     }
 }
 
+/// Links PTX `modules` into a single module at `output_path`.
+///
+/// The PTX emitted by `rustc` is not relocatable device code, so the modules
+/// are combined at the source level instead of with `nvlink`: they have to
+/// share the `.target` and `.address_size` and must not define the same
+/// function or kernel twice.
+///
+/// # Usage
+/// ```no_run
+/// use ptx_builder::prelude::*;
+/// # use ptx_builder::error::Result;
+///
+/// # fn main() -> Result<()> {
+/// link_ptx(&["first.ptx", "second.ptx"], "linked.ptx".as_ref())?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn link_ptx<P: AsRef<Path>>(modules: &[P], output_path: &Path) -> Result<()> {
+    let modules = modules
+        .iter()
+        .map(|path| read_to_string(path).context(BuildErrorKind::OtherError))
+        .collect::<Result<Vec<_>>>()?;
+
+    let linked = ptx::link(&modules)
+        .map_err(|messages| Error::from(BuildErrorKind::LinkFailed { messages }))?;
+
+    write(output_path, linked).context(BuildErrorKind::OtherError)?;
+
+    Ok(())
+}
+
+impl Profile {
+    /// Returns the name of the profile output directory.
+    fn get_dir_name(&self) -> &str {
+        match self {
+            Profile::Debug => "debug",
+            Profile::Release => "release",
+
+            // Cargo keeps the legacy directories of the built-in profiles.
+            Profile::Custom(name) => match name.as_str() {
+                "dev" | "test" => "debug",
+                "bench" => "release",
+                name => name,
+            },
+        }
+    }
+}
+
 impl fmt::Display for Profile {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Profile::Debug => write!(fmt, "debug"),
             Profile::Release => write!(fmt, "release"),
+            Profile::Custom(name) => write!(fmt, "{name}"),
+        }
+    }
+}
+
+impl fmt::Display for NvptxTarget {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NvptxTarget::Nvptx32 => write!(fmt, "nvptx-nvidia-cuda"),
+            NvptxTarget::Nvptx64 => write!(fmt, "nvptx64-nvidia-cuda"),
+        }
+    }
+}
+
+impl OutputKind {
+    fn get_extension(self) -> &'static str {
+        match self {
+            OutputKind::Ptx => "ptx",
+            OutputKind::LlvmIr => "ll",
+            OutputKind::LlvmBc => "bc",
+        }
+    }
+}
+
+impl fmt::Display for OptLevel {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptLevel::O0 => write!(fmt, "0"),
+            OptLevel::O1 => write!(fmt, "1"),
+            OptLevel::O2 => write!(fmt, "2"),
+            OptLevel::O3 => write!(fmt, "3"),
+            OptLevel::Os => write!(fmt, "s"),
+            OptLevel::Oz => write!(fmt, "z"),
+        }
+    }
+}
+
+impl fmt::Display for LtoMode {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LtoMode::Off => write!(fmt, "off"),
+            LtoMode::Thin => write!(fmt, "thin"),
+            LtoMode::Fat => write!(fmt, "fat"),
+        }
+    }
+}
+
+impl fmt::Display for PanicStrategy {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PanicStrategy::Abort => write!(fmt, "abort"),
+            PanicStrategy::Unwind => write!(fmt, "unwind"),
+        }
+    }
+}
+
+impl fmt::Display for DebugInfo {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DebugInfo::None => write!(fmt, "0"),
+            DebugInfo::LineTables => write!(fmt, "line-tables-only"),
+            DebugInfo::Full => write!(fmt, "2"),
+        }
+    }
+}
+
+impl ComputeCapability {
+    fn get_sm_version(self) -> u32 {
+        match self {
+            ComputeCapability::Sm35 => 35,
+            ComputeCapability::Sm37 => 37,
+            ComputeCapability::Sm50 => 50,
+            ComputeCapability::Sm52 => 52,
+            ComputeCapability::Sm53 => 53,
+            ComputeCapability::Sm60 => 60,
+            ComputeCapability::Sm61 => 61,
+            ComputeCapability::Sm62 => 62,
+            ComputeCapability::Sm70 => 70,
+            ComputeCapability::Sm72 => 72,
+            ComputeCapability::Sm75 => 75,
+            ComputeCapability::Sm80 => 80,
+            ComputeCapability::Sm86 => 86,
+            ComputeCapability::Sm87 => 87,
+            ComputeCapability::Sm89 => 89,
+            ComputeCapability::Sm90 => 90,
+        }
+    }
+}
+
+impl fmt::Display for ComputeCapability {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "sm_{}", self.get_sm_version())
+    }
+}
+
+impl FromStr for ComputeCapability {
+    type Err = Error;
+
+    fn from_str(compute_capability: &str) -> Result<Self> {
+        match compute_capability {
+            "sm_35" => Ok(ComputeCapability::Sm35),
+            "sm_37" => Ok(ComputeCapability::Sm37),
+            "sm_50" => Ok(ComputeCapability::Sm50),
+            "sm_52" => Ok(ComputeCapability::Sm52),
+            "sm_53" => Ok(ComputeCapability::Sm53),
+            "sm_60" => Ok(ComputeCapability::Sm60),
+            "sm_61" => Ok(ComputeCapability::Sm61),
+            "sm_62" => Ok(ComputeCapability::Sm62),
+            "sm_70" => Ok(ComputeCapability::Sm70),
+            "sm_72" => Ok(ComputeCapability::Sm72),
+            "sm_75" => Ok(ComputeCapability::Sm75),
+            "sm_80" => Ok(ComputeCapability::Sm80),
+            "sm_86" => Ok(ComputeCapability::Sm86),
+            "sm_87" => Ok(ComputeCapability::Sm87),
+            "sm_89" => Ok(ComputeCapability::Sm89),
+            "sm_90" => Ok(ComputeCapability::Sm90),
+            _ => Err(BuildErrorKind::InvalidComputeCapability(compute_capability.into()).into()),
+        }
+    }
+}
+
+impl FromStr for CrateType {
+    type Err = Error;
+
+    fn from_str(crate_type: &str) -> Result<Self> {
+        match crate_type {
+            "lib" | "library" => Ok(CrateType::Library),
+            "bin" | "binary" => Ok(CrateType::Binary),
+            "mixed" => Ok(CrateType::Mixed),
+            _ => Err(BuildErrorKind::InvalidCrateType(crate_type.into()).into()),
+        }
+    }
+}
+
+impl TryFrom<&str> for CrateType {
+    type Error = Error;
+
+    fn try_from(crate_type: &str) -> Result<Self> {
+        crate_type.parse()
+    }
+}
+
+enum OutputLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+/// Collects the `cargo` messages, while forwarding the output lines.
+struct CargoListener<'a, O, E> {
+    builder: &'a Builder,
+    forward_json: bool,
+    on_stdout_line: RefCell<O>,
+    on_stderr_line: RefCell<E>,
+    diagnostics: RefCell<Vec<Diagnostic>>,
+    lines: RefCell<Vec<String>>,
+    artifacts: RefCell<Vec<Artifact>>,
+}
+
+impl<'a, O: FnMut(&str), E: FnMut(&str)> CargoListener<'a, O, E> {
+    fn new(builder: &'a Builder, on_stdout_line: O, on_stderr_line: E) -> Self {
+        CargoListener {
+            builder,
+            forward_json: matches!(builder.message_format, MessageFormat::Json { .. }),
+            on_stdout_line: RefCell::new(on_stdout_line),
+            on_stderr_line: RefCell::new(on_stderr_line),
+            diagnostics: RefCell::new(Vec::new()),
+            lines: RefCell::new(Vec::new()),
+            artifacts: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn on_stdout_line(&self, line: &str) {
+        if self.forward_json || !line.starts_with('{') {
+            (self.on_stdout_line.borrow_mut())(line);
+        }
+
+        // Tooling gets the JSON messages of a failed build as they are.
+        if self.forward_json && line.starts_with('{') {
+            self.lines.borrow_mut().push(String::from(line));
+        }
+
+        match CargoMessage::parse(line) {
+            Some(CargoMessage::CompilerMessage { message }) => {
+                if let (false, Some(rendered)) = (self.forward_json, &message.rendered) {
+                    for rendered_line in rendered.lines() {
+                        if !self.builder.is_kept_output_line(rendered_line) {
+                            continue;
+                        }
+
+                        self.lines.borrow_mut().push(String::from(rendered_line));
+                        (self.on_stderr_line.borrow_mut())(rendered_line);
+                    }
+                }
+
+                self.diagnostics.borrow_mut().push(message);
+            }
+
+            Some(CargoMessage::CompilerArtifact(artifact)) => {
+                self.artifacts.borrow_mut().push(artifact);
+            }
+
+            _ => {}
+        }
+    }
+
+    fn on_stderr_line(&self, line: &str) {
+        if let Some(crate_name) = Builder::parse_compiling_crate(line) {
+            self.builder
+                .notify_progress(BuildEvent::CompilingCrate(crate_name));
         }
+
+        if Builder::output_is_not_verbose(line) && self.builder.is_kept_output_line(line) {
+            self.lines.borrow_mut().push(String::from(line));
+            (self.on_stderr_line.borrow_mut())(line);
+        }
+    }
+
+    /// Discards the collected output of a failed attempt before a retry.
+    fn reset(&self) {
+        self.diagnostics.borrow_mut().clear();
+        self.lines.borrow_mut().clear();
+        self.artifacts.borrow_mut().clear();
     }
 }
 
@@ -600,3 +4318,173 @@ impl std::ops::Deref for BuildCommand {
         }
     }
 }
+
+#[test]
+fn should_separate_toolchains() {
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+    let nightly_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_toolchain("nightly");
+
+    let output_location = |builder: &Builder| {
+        builder
+            .source_crate
+            .get_output_location(None, &builder.get_build_config(None))
+    };
+
+    assert_ne!(output_location(&nightly_builder), output_location(&builder));
+}
+
+#[test]
+fn should_report_missing_target() {
+    struct Echo;
+
+    impl Executable for Echo {
+        fn get_name(&self) -> String {
+            String::from("echo")
+        }
+
+        fn get_verification_hint(&self) -> String {
+            String::from("Some useful hint")
+        }
+
+        fn get_version_hint(&self) -> String {
+            String::from("Some useful hint about version")
+        }
+
+        fn get_required_version(&self) -> Option<semver::VersionReq> {
+            None
+        }
+
+        fn get_current_version(&self) -> Result<semver::Version> {
+            Ok(semver::Version::new(1, 0, 0))
+        }
+    }
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    // `echo` prints its arguments as the sysroot, which doesn't exist
+    match builder.check_target(Echo).unwrap_err().kind() {
+        BuildErrorKind::TargetNotInstalled { target, hint } => {
+            assert_eq!(target, "nvptx64-nvidia-cuda");
+            assert_eq!(
+                hint,
+                "Please install it with `rustup target add nvptx64-nvidia-cuda`"
+            );
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    let sysroot = env::temp_dir().join("ptx-builder-target-sysroot");
+    let target_libs = sysroot.join("lib/rustlib/nvptx64-nvidia-cuda/lib");
+    remove_dir_all(&sysroot).unwrap_or_default();
+
+    match Builder::check_target_in_sysroot(&sysroot, NvptxTarget::Nvptx64)
+        .unwrap_err()
+        .kind()
+    {
+        BuildErrorKind::TargetNotInstalled { target, .. } => {
+            assert_eq!(target, "nvptx64-nvidia-cuda");
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    create_dir_all(&target_libs).unwrap();
+    assert!(Builder::check_target_in_sysroot(&sysroot, NvptxTarget::Nvptx64).is_ok());
+
+    remove_dir_all(&sysroot).unwrap_or_default();
+}
+
+#[test]
+fn should_report_missing_linker() {
+    struct UnlikelyLinker;
+
+    impl Executable for UnlikelyLinker {
+        fn get_name(&self) -> String {
+            String::from("almost-unique-ptx-linker")
+        }
+
+        fn get_verification_hint(&self) -> String {
+            String::from("Some useful hint")
+        }
+
+        fn get_version_hint(&self) -> String {
+            String::from("Some useful hint about version")
+        }
+
+        fn get_required_version(&self) -> Option<semver::VersionReq> {
+            None
+        }
+    }
+
+    match Builder::check_linker(UnlikelyLinker).unwrap_err().kind() {
+        BuildErrorKind::CommandNotFound { command, hint } => {
+            assert_eq!(command, "almost-unique-ptx-linker");
+            assert_eq!(hint, "Some useful hint");
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    for linker in [
+        "almost-unique-ptx-linker",
+        "/missing/bin/almost-unique-ptx-linker",
+    ] {
+        match Builder::check_custom_linker(linker).unwrap_err().kind() {
+            BuildErrorKind::CommandNotFound { command, .. } => assert_eq!(command, linker),
+            _ => unreachable!("it should fail with proper error"),
+        }
+    }
+
+    assert!(Builder::check_custom_linker("cargo").is_ok());
+}
+
+#[test]
+fn should_parse_crate_type() {
+    assert_eq!("lib".parse::<CrateType>().unwrap(), CrateType::Library);
+    assert_eq!("library".parse::<CrateType>().unwrap(), CrateType::Library);
+    assert_eq!("bin".parse::<CrateType>().unwrap(), CrateType::Binary);
+    assert_eq!(CrateType::try_from("binary").unwrap(), CrateType::Binary);
+    assert_eq!(CrateType::try_from("mixed").unwrap(), CrateType::Mixed);
+
+    for crate_type in ["cdylib", ""] {
+        match CrateType::try_from(crate_type).unwrap_err().kind() {
+            BuildErrorKind::InvalidCrateType(kind) => assert_eq!(kind, crate_type),
+            _ => unreachable!("it should fail with InvalidCrateType"),
+        }
+    }
+}
+
+#[test]
+fn should_provide_build_status_helpers() {
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+    let assembly_path = PathBuf::from("/output/sample_ptx_crate.ptx");
+
+    let success = || {
+        BuildStatus::Success(BuildOutput::with_assembly_path(
+            &builder,
+            PathBuf::from("/output"),
+            assembly_path.clone(),
+        ))
+    };
+
+    assert!(success().is_success());
+    assert!(!success().is_not_needed());
+    assert_eq!(
+        success().into_output().unwrap().get_assembly_path(),
+        assembly_path
+    );
+    assert_eq!(success().unwrap_output().get_assembly_path(), assembly_path);
+
+    assert!(!BuildStatus::NotNeeded.is_success());
+    assert!(BuildStatus::NotNeeded.is_not_needed());
+    assert!(BuildStatus::NotNeeded.into_output().is_none());
+}
+
+#[test]
+#[should_panic(expected = "called `BuildStatus::unwrap_output()` on a `NotNeeded` value")]
+fn should_panic_on_unwrapping_not_needed_output() {
+    let _ = BuildStatus::NotNeeded.unwrap_output();
+}
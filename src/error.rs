@@ -1,7 +1,13 @@
-use std::{fmt, path::PathBuf};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
 
 use colored::Colorize;
+use regex::Regex;
 use semver::{Version, VersionReq};
+use serde::Deserialize;
 
 #[macro_export]
 macro_rules! bail {
@@ -88,6 +94,165 @@ impl<'a, T, E: std::error::Error + Send + Sync + 'static> ResultExt<T, &'a str>
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// Compiler diagnostic reported by `rustc` during the build.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+pub struct Diagnostic {
+    /// Severity of the diagnostic, e.g. `error` or `warning`.
+    pub level: String,
+
+    /// Primary message of the diagnostic.
+    pub message: String,
+
+    /// Error code of the diagnostic, e.g. `E0425`.
+    #[serde(deserialize_with = "deserialize_diagnostic_code")]
+    pub code: Option<String>,
+
+    /// Source locations the diagnostic refers to.
+    pub spans: Vec<DiagnosticSpan>,
+
+    /// Diagnostic rendered in the same way as `rustc` prints it.
+    pub rendered: Option<String>,
+}
+
+/// Source location of a [`Diagnostic`].
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+pub struct DiagnosticSpan {
+    /// Path to the file, relative to the crate root.
+    pub file_name: String,
+
+    /// 1-based line where the span starts.
+    pub line_start: usize,
+
+    /// 1-based line where the span ends.
+    pub line_end: usize,
+
+    /// 1-based column where the span starts.
+    pub column_start: usize,
+
+    /// 1-based column where the span ends.
+    pub column_end: usize,
+
+    /// Whether the span is the primary location of the diagnostic.
+    pub is_primary: bool,
+
+    /// Label attached to the span.
+    pub label: Option<String>,
+
+    /// Source lines covered by the span.
+    #[serde(default)]
+    pub text: Vec<DiagnosticSpanLine>,
+}
+
+/// Source line of a [`DiagnosticSpan`].
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+pub struct DiagnosticSpanLine {
+    /// The whole source line.
+    pub text: String,
+
+    /// 1-based column where the highlighted part starts.
+    pub highlight_start: usize,
+
+    /// 1-based column after the highlighted part.
+    pub highlight_end: usize,
+}
+
+impl Diagnostic {
+    /// Whether it's the `aborting due to N previous errors` summary of
+    /// `rustc`, rather than an actual error.
+    pub(crate) fn is_abort_summary(&self) -> bool {
+        static SUMMARY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^aborting due to (\d+ )?previous errors?")
+                .expect("Unable to parse regex...")
+        });
+
+        SUMMARY_REGEX.is_match(&self.message)
+    }
+
+    /// Returns the primary location of the diagnostic, if any.
+    #[must_use]
+    pub fn primary_span(&self) -> Option<&DiagnosticSpan> {
+        self.spans.iter().find(|span| span.is_primary)
+    }
+
+    /// Renders the diagnostic as a GitHub Actions workflow command, that
+    /// annotates the source of the primary span.
+    ///
+    /// The span file names are relative to the crate root, so the
+    /// `crate_path` relative to the repository root is prepended.
+    ///
+    /// # Usage
+    /// ```no_run
+    /// use std::path::Path;
+    /// use ptx_builder::error::BuildErrorKind;
+    /// use ptx_builder::prelude::*;
+    ///
+    /// if let Err(error) = Builder::new("kernel").unwrap().build() {
+    ///     for diagnostic in error.kind().diagnostics().unwrap_or_default() {
+    ///         println!("{}", diagnostic.github_annotation(Path::new("kernel")));
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub fn github_annotation(&self, crate_path: &Path) -> String {
+        let command = match self.level.as_str() {
+            "error" | "error: internal compiler error" => "error",
+            "warning" => "warning",
+            _ => "notice",
+        };
+
+        let mut properties = Vec::new();
+
+        if let Some(span) = self.primary_span() {
+            let file = crate_path.join(&span.file_name);
+
+            properties.push(format!(
+                "file={}",
+                escape_annotation_property(&file.display().to_string())
+            ));
+
+            properties.push(format!("line={}", span.line_start));
+            properties.push(format!("endLine={}", span.line_end));
+            properties.push(format!("col={}", span.column_start));
+            properties.push(format!("endColumn={}", span.column_end));
+        }
+
+        if let Some(code) = &self.code {
+            properties.push(format!("title={}", escape_annotation_property(code)));
+        }
+
+        let separator = if properties.is_empty() { "" } else { " " };
+
+        format!(
+            "::{command}{separator}{}::{}",
+            properties.join(","),
+            escape_annotation_data(&self.message)
+        )
+    }
+}
+
+fn escape_annotation_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_annotation_property(property: &str) -> String {
+    escape_annotation_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+fn deserialize_diagnostic_code<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    struct DiagnosticCode {
+        code: String,
+    }
+
+    Ok(Option::<DiagnosticCode>::deserialize(deserializer)?.map(|code| code.code))
+}
+
 #[derive(Debug, PartialEq, Eq, thiserror::Error, Clone)]
 pub enum BuildErrorKind {
     CommandNotFound {
@@ -107,19 +272,106 @@ pub enum BuildErrorKind {
         hint: String,
     },
 
+    ToolchainNotInstalled {
+        toolchain: String,
+        hint: String,
+    },
+
+    TargetNotInstalled {
+        target: String,
+        hint: String,
+    },
+
+    CudaToolkitNotFound,
+    MissingLockfile(PathBuf),
+
     InvalidCratePath(PathBuf),
-    BuildFailed(Vec<String>),
+    InvalidSysroot(PathBuf),
+    WorkspaceMemberRequired(Vec<String>),
+    BuildFailed {
+        diagnostics: Vec<Diagnostic>,
+        lines: Vec<String>,
+        command: Vec<String>,
+    },
+    PtxValidationFailed {
+        messages: Vec<String>,
+    },
+    LinkFailed {
+        messages: Vec<String>,
+    },
+    NoKernelsFound(PathBuf),
+    OutputDirNotWritable {
+        path: PathBuf,
+    },
     InvalidCrateType(String),
+    InvalidCrateName(String),
+    BinaryNotFound {
+        name: String,
+        binaries: Vec<String>,
+    },
+    ExampleNotFound {
+        name: String,
+        examples: Vec<String>,
+    },
+    InvalidComputeCapability(String),
+    UnsupportedComputeCapability {
+        requested: String,
+        supported: Vec<String>,
+    },
+    UnsupportedPtxVersion {
+        requested: String,
+        supported: Vec<String>,
+    },
+    UnsupportedRustcCrateType {
+        requested: String,
+        supported: Vec<String>,
+    },
+    InvalidUnstableFlag(String),
     MissingCrateType,
     InternalError(String),
     OtherError,
 }
 
+impl BuildErrorKind {
+    /// Structured compiler diagnostics of a failed build.
+    #[must_use]
+    pub fn diagnostics(&self) -> Option<&[Diagnostic]> {
+        match self {
+            BuildErrorKind::BuildFailed { diagnostics, .. } => Some(diagnostics),
+            _ => None,
+        }
+    }
+
+    /// Raw output lines of a failed build, as `cargo` printed them.
+    #[must_use]
+    pub fn raw_lines(&self) -> Option<&[String]> {
+        match self {
+            BuildErrorKind::BuildFailed { lines, .. } => Some(lines),
+            _ => None,
+        }
+    }
+
+    /// The `cargo` command line of a failed build, to reproduce it.
+    #[must_use]
+    pub fn failed_command(&self) -> Option<&[String]> {
+        match self {
+            BuildErrorKind::BuildFailed { command, .. } => Some(command),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for BuildErrorKind {
+    #[allow(clippy::too_many_lines)]
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         use BuildErrorKind::{
-            BuildFailed, CommandFailed, CommandNotFound, CommandVersionNotFulfilled, InternalError,
-            InvalidCratePath, InvalidCrateType, MissingCrateType, OtherError,
+            BinaryNotFound, BuildFailed, CommandFailed, CommandNotFound,
+            CommandVersionNotFulfilled, CudaToolkitNotFound, ExampleNotFound, InternalError,
+            InvalidComputeCapability, InvalidCrateName, InvalidCratePath, InvalidCrateType,
+            InvalidSysroot, InvalidUnstableFlag, LinkFailed, MissingCrateType, MissingLockfile,
+            NoKernelsFound, OtherError, OutputDirNotWritable, PtxValidationFailed,
+            TargetNotInstalled, ToolchainNotInstalled, UnsupportedComputeCapability,
+            UnsupportedPtxVersion, UnsupportedRustcCrateType, WorkspaceMemberRequired,
         };
 
         match self {
@@ -149,14 +401,42 @@ impl fmt::Display for BuildErrorKind {
                 hint,
             } => write!(
                 fmt,
-                "Command version is not fulfilled: '{}' is currently '{}' but '{}' is required. \
-                 {}.",
+                "Command version is not fulfilled: '{}' found '{}', requires '{}'. {}.",
                 command.bold(),
                 current.to_string().underline(),
                 required.to_string().underline(),
                 hint.underline(),
             ),
 
+            ToolchainNotInstalled { toolchain, hint } => write!(
+                fmt,
+                "Toolchain is not installed: '{}'. {}.",
+                toolchain.bold(),
+                hint.underline()
+            ),
+
+            TargetNotInstalled { target, hint } => write!(
+                fmt,
+                "Target is not supported by the toolchain: '{}'. {}.",
+                target.bold(),
+                hint.underline()
+            ),
+
+            CudaToolkitNotFound => write!(
+                fmt,
+                "{}. {}.",
+                "CUDA toolkit not found".bold(),
+                "Please set `CUDA_PATH` to the toolkit installation directory".underline()
+            ),
+
+            MissingLockfile(path) => write!(
+                fmt,
+                "{}: {}. {}.",
+                "Cargo.lock is required for a locked build".bold(),
+                path.display(),
+                "Please generate it with `cargo generate-lockfile`".underline()
+            ),
+
             InvalidCratePath(path) => write!(
                 fmt,
                 "{}: {}",
@@ -164,11 +444,69 @@ impl fmt::Display for BuildErrorKind {
                 path.display()
             ),
 
-            BuildFailed(lines) => write!(
+            InvalidSysroot(path) => {
+                write!(fmt, "{}: {}", "Invalid sysroot path".bold(), path.display())
+            }
+
+            WorkspaceMemberRequired(members) => write!(
+                fmt,
+                "{}: the workspace has members '{}'",
+                "Workspace member required".bold(),
+                members.join("', '")
+            ),
+
+            BuildFailed {
+                diagnostics, lines, ..
+            } => {
+                write!(fmt, "{}", "Unable to build a PTX crate!".bold())?;
+
+                let mut errors = diagnostics.iter().filter(|diagnostic| {
+                    diagnostic.level == "error" && !diagnostic.is_abort_summary()
+                });
+
+                if let Some(first_error) = errors.next() {
+                    let count = errors.count() + 1;
+
+                    write!(
+                        fmt,
+                        " {} error{}, the first: {}",
+                        count,
+                        if count == 1 { "" } else { "s" },
+                        first_error.message
+                    )?;
+                }
+
+                write!(fmt, "\n{}", lines.join("\n"))
+            }
+
+            PtxValidationFailed { messages } => write!(
                 fmt,
                 "{}\n{}",
-                "Unable to build a PTX crate!".bold(),
-                lines.join("\n")
+                "PTX assembly was rejected by `ptxas`!".bold(),
+                messages.join("\n")
+            ),
+
+            LinkFailed { messages } => write!(
+                fmt,
+                "{}\n{}",
+                "Unable to link PTX modules!".bold(),
+                messages.join("\n")
+            ),
+
+            NoKernelsFound(path) => write!(
+                fmt,
+                "{}: {}. Please declare them as `{}` functions.",
+                "No kernels found in the PTX assembly".bold(),
+                path.display(),
+                "extern \"ptx-kernel\"".underline()
+            ),
+
+            OutputDirNotWritable { path } => write!(
+                fmt,
+                "{}: {}. Please set a writable location with `{}`.",
+                "Output directory is not writable".bold(),
+                path.display(),
+                "Builder::with_target_dir".underline()
             ),
 
             InvalidCrateType(crate_type) => write!(
@@ -178,6 +516,77 @@ impl fmt::Display for BuildErrorKind {
                 crate_type
             ),
 
+            InvalidCrateName(name) => write!(
+                fmt,
+                "{}: '{}' cannot be used as a filename prefix",
+                "Invalid crate name".bold(),
+                name
+            ),
+
+            BinaryNotFound { name, binaries } => write!(
+                fmt,
+                "{}: '{}'. The crate has binaries '{}'",
+                "Binary not found".bold(),
+                name,
+                binaries.join("', '")
+            ),
+
+            ExampleNotFound { name, examples } => write!(
+                fmt,
+                "{}: '{}'. The crate has examples '{}'",
+                "Example not found".bold(),
+                name,
+                examples.join("', '")
+            ),
+
+            InvalidComputeCapability(compute_capability) => write!(
+                fmt,
+                "{}: '{}' is not a known SM architecture",
+                "Invalid ComputeCapability".bold(),
+                compute_capability
+            ),
+
+            InvalidUnstableFlag(flag) => write!(
+                fmt,
+                "{}: '{}' doesn't start with `-Z`",
+                "Invalid unstable flag".bold(),
+                flag
+            ),
+
+            UnsupportedComputeCapability {
+                requested,
+                supported,
+            } => write!(
+                fmt,
+                "{}: '{}' is not supported by the installed `ptxas`. It supports '{}'. {}.",
+                "Unsupported ComputeCapability".bold(),
+                requested,
+                supported.join("', '"),
+                "Please update the CUDA toolkit".underline()
+            ),
+
+            UnsupportedPtxVersion {
+                requested,
+                supported,
+            } => write!(
+                fmt,
+                "{}: '{}' can't be emitted by LLVM. It supports '{}'.",
+                "Unsupported PTX version".bold(),
+                requested,
+                supported.join("', '")
+            ),
+
+            UnsupportedRustcCrateType {
+                requested,
+                supported,
+            } => write!(
+                fmt,
+                "{}: '{}'. It supports '{}'.",
+                "Unsupported rustc crate type".bold(),
+                requested,
+                supported.join("', '")
+            ),
+
             MissingCrateType => write!(
                 fmt,
                 "{}: it's mandatory for mixed-type crates",
@@ -189,3 +598,190 @@ impl fmt::Display for BuildErrorKind {
         }
     }
 }
+
+#[test]
+fn should_display_build_failure() {
+    colored::control::set_override(false);
+
+    let diagnostic = |level: &str, message: &str| Diagnostic {
+        level: String::from(level),
+        message: String::from(message),
+        code: None,
+        spans: Vec::new(),
+        rendered: None,
+    };
+
+    let build_error = BuildErrorKind::BuildFailed {
+        diagnostics: vec![
+            diagnostic("warning", "unused variable: `unused`"),
+            diagnostic("error", "cannot find function `external_fn` in this scope"),
+            diagnostic("error", "aborting due to 1 previous error"),
+        ],
+        lines: vec![String::from(
+            "error[E0425]: cannot find function `external_fn`",
+        )],
+        command: vec![String::from("cargo"), String::from("rustc")],
+    };
+
+    assert_eq!(
+        build_error.to_string(),
+        "Unable to build a PTX crate! 1 error, the first: cannot find function `external_fn` in \
+         this scope\nerror[E0425]: cannot find function `external_fn`"
+    );
+
+    let build_error = BuildErrorKind::BuildFailed {
+        diagnostics: vec![
+            diagnostic("error", "cannot find function `first_fn` in this scope"),
+            diagnostic("error", "cannot find function `second_fn` in this scope"),
+            diagnostic(
+                "error",
+                "aborting due to 2 previous errors; 1 warning emitted",
+            ),
+        ],
+        lines: Vec::new(),
+        command: Vec::new(),
+    };
+
+    assert!(build_error.to_string().starts_with(
+        "Unable to build a PTX crate! 2 errors, the first: cannot find function `first_fn`"
+    ));
+}
+
+#[test]
+fn should_display_error_kinds() {
+    colored::control::set_override(false);
+
+    let version_error = BuildErrorKind::CommandVersionNotFulfilled {
+        command: String::from("cargo"),
+        current: Version::new(1, 2, 3),
+        required: VersionReq::parse(">= 1.34.0").unwrap(),
+        hint: String::from("Please update Rust"),
+    };
+
+    assert!(version_error
+        .to_string()
+        .contains("'cargo' found '1.2.3', requires '>=1.34.0'. Please update Rust."));
+
+    assert!(BuildErrorKind::MissingLockfile(PathBuf::from("/crate"))
+        .to_string()
+        .contains("Cargo.lock is required for a locked build: /crate."));
+
+    assert_eq!(
+        BuildErrorKind::InvalidComputeCapability(String::from("sm_00")).to_string(),
+        "Invalid ComputeCapability: 'sm_00' is not a known SM architecture"
+    );
+
+    assert_eq!(
+        BuildErrorKind::PtxValidationFailed {
+            messages: vec![
+                String::from("ptxas kernel.ptx, line 1; error   : Unknown directive"),
+                String::from("ptxas fatal   : Ptx assembly aborted due to errors"),
+            ],
+        }
+        .to_string(),
+        "PTX assembly was rejected by `ptxas`!\nptxas kernel.ptx, line 1; error   : Unknown \
+         directive\nptxas fatal   : Ptx assembly aborted due to errors"
+    );
+
+    assert_eq!(
+        BuildErrorKind::LinkFailed {
+            messages: vec![String::from("`the_kernel` is defined in modules #1 and #2")],
+        }
+        .to_string(),
+        "Unable to link PTX modules!\n`the_kernel` is defined in modules #1 and #2"
+    );
+
+    assert_eq!(
+        BuildErrorKind::NoKernelsFound(PathBuf::from("/output/kernels.ptx")).to_string(),
+        "No kernels found in the PTX assembly: /output/kernels.ptx. Please declare them as \
+         `extern \"ptx-kernel\"` functions."
+    );
+
+    assert_eq!(
+        BuildErrorKind::OutputDirNotWritable {
+            path: PathBuf::from("/output"),
+        }
+        .to_string(),
+        "Output directory is not writable: /output. Please set a writable location with \
+         `Builder::with_target_dir`."
+    );
+
+    assert_eq!(
+        BuildErrorKind::UnsupportedComputeCapability {
+            requested: String::from("sm_90"),
+            supported: vec![String::from("sm_50"), String::from("sm_86")],
+        }
+        .to_string(),
+        "Unsupported ComputeCapability: 'sm_90' is not supported by the installed `ptxas`. It \
+         supports 'sm_50', 'sm_86'. Please update the CUDA toolkit."
+    );
+
+    assert_eq!(
+        BuildErrorKind::InvalidSysroot(PathBuf::from("/opt/sysroot")).to_string(),
+        "Invalid sysroot path: /opt/sysroot"
+    );
+
+    assert_eq!(
+        BuildErrorKind::InvalidUnstableFlag(String::from("-C opt-level=3")).to_string(),
+        "Invalid unstable flag: '-C opt-level=3' doesn't start with `-Z`"
+    );
+
+    assert_eq!(
+        BuildErrorKind::UnsupportedPtxVersion {
+            requested: String::from("9.9"),
+            supported: vec![String::from("7.0"), String::from("7.1")],
+        }
+        .to_string(),
+        "Unsupported PTX version: '9.9' can't be emitted by LLVM. It supports '7.0', '7.1'."
+    );
+
+    assert_eq!(
+        BuildErrorKind::UnsupportedRustcCrateType {
+            requested: String::from("staticlib"),
+            supported: vec![String::from("lib"), String::from("cdylib")],
+        }
+        .to_string(),
+        "Unsupported rustc crate type: 'staticlib'. It supports 'lib', 'cdylib'."
+    );
+}
+
+#[test]
+fn should_render_github_annotations() {
+    let span = DiagnosticSpan {
+        file_name: String::from("src/lib.rs"),
+        line_start: 7,
+        line_end: 7,
+        column_start: 20,
+        column_end: 31,
+        is_primary: true,
+        label: None,
+        text: Vec::new(),
+    };
+
+    let diagnostic = Diagnostic {
+        level: String::from("error"),
+        message: String::from("cannot find function `external_fn`\n100% sure"),
+        code: Some(String::from("E0425")),
+        spans: vec![span],
+        rendered: None,
+    };
+
+    assert_eq!(
+        diagnostic.github_annotation(Path::new("kernels/a,b")),
+        "::error file=kernels/a%2Cb/src/lib.rs,line=7,endLine=7,col=20,endColumn=31,title=E0425::\
+         cannot find function `external_fn`%0A100%25 sure"
+    );
+
+    let summary = Diagnostic {
+        level: String::from("warning"),
+        message: String::from("unused crate"),
+        code: None,
+        spans: Vec::new(),
+        rendered: None,
+    };
+
+    assert_eq!(
+        summary.github_annotation(Path::new(".")),
+        "::warning::unused crate"
+    );
+}
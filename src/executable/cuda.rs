@@ -0,0 +1,58 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use crate::error::{BuildErrorKind, Result};
+
+/// Location of the CUDA toolkit installation.
+#[derive(Clone, Debug)]
+pub struct CudaToolkit {
+    path: PathBuf,
+}
+
+impl CudaToolkit {
+    /// Locates the toolkit via `CUDA_PATH`, `CUDA_HOME` or `nvcc` in `PATH`.
+    pub fn locate() -> Result<Self> {
+        for var in ["CUDA_PATH", "CUDA_HOME"] {
+            if let Some(path) = env::var_os(var).map(PathBuf::from) {
+                if path.join("bin").is_dir() {
+                    return Ok(CudaToolkit { path });
+                }
+            }
+        }
+
+        let paths = env::var_os("PATH").unwrap_or_default();
+
+        for dir in env::split_paths(&paths) {
+            if !dir
+                .join(format!("nvcc{}", env::consts::EXE_SUFFIX))
+                .is_file()
+            {
+                continue;
+            }
+
+            if let Some(path) = dir.parent() {
+                return Ok(CudaToolkit {
+                    path: path.to_path_buf(),
+                });
+            }
+        }
+
+        bail!(BuildErrorKind::CudaToolkitNotFound);
+    }
+
+    /// Returns the toolkit root directory.
+    #[must_use]
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the path of the toolkit executable `name`.
+    #[must_use]
+    pub fn get_executable_path(&self, name: &str) -> PathBuf {
+        self.path
+            .join("bin")
+            .join(format!("{name}{}", env::consts::EXE_SUFFIX))
+    }
+}
@@ -1,9 +1,13 @@
+use std::{path::PathBuf, process::Command};
+
 use semver::{Version, VersionReq};
 
 use crate::error::Result;
 
+mod cuda;
 mod process;
 pub mod runner;
+pub use self::cuda::CudaToolkit;
 #[allow(clippy::module_name_repetitions)]
 pub use self::runner::{ExecutableRunner, Output};
 
@@ -12,6 +16,11 @@ pub trait Executable {
     /// Returns executable name in `PATH`.
     fn get_name(&self) -> String;
 
+    /// Returns the path to run the executable from, its name by default.
+    fn get_path(&self) -> PathBuf {
+        PathBuf::from(self.get_name())
+    }
+
     /// Returns message about how to install missing executable.
     fn get_verification_hint(&self) -> String;
 
@@ -28,6 +37,18 @@ pub trait Executable {
     {
         self::runner::parse_executable_version(self)
     }
+
+    /// Returns the current version of the executable, when it's run by the
+    /// `command`, e.g. in the working directory of the actual invocation.
+    ///
+    /// Falls back to [`Executable::get_current_version`] by default.
+    fn get_current_version_with(&self, command: Command) -> Result<Version>
+    where
+        Self: Sized,
+    {
+        let _ = command;
+        self.get_current_version()
+    }
 }
 
 /// `cargo` command.
@@ -51,12 +72,169 @@ impl Executable for Cargo {
     }
 
     fn get_current_version(&self) -> Result<Version> {
+        self.get_current_version_with(Command::new(self.get_path()))
+    }
+
+    fn get_current_version_with(&self, command: Command) -> Result<Version> {
         // Omit Rust channel name because it's not really semver-correct
         // https://github.com/steveklabnik/semver/issues/105
 
-        self::runner::parse_executable_version(self).map(|mut version| {
+        self::runner::parse_command_version(self, command).map(|mut version| {
             version.pre = semver::Prerelease::EMPTY;
             version
         })
     }
 }
+
+/// `rustc` command.
+pub struct Rustc;
+
+impl Executable for Rustc {
+    fn get_name(&self) -> String {
+        String::from("rustc")
+    }
+
+    fn get_verification_hint(&self) -> String {
+        String::from("Please make sure you have it installed and in PATH")
+    }
+
+    fn get_version_hint(&self) -> String {
+        String::from("Please update Rust and Cargo to latest nightly versions")
+    }
+
+    fn get_required_version(&self) -> Option<VersionReq> {
+        Some(VersionReq::parse(">= 1.34.0-nightly").unwrap())
+    }
+
+    fn get_current_version(&self) -> Result<Version> {
+        self.get_current_version_with(Command::new(self.get_path()))
+    }
+
+    fn get_current_version_with(&self, command: Command) -> Result<Version> {
+        // Omit Rust channel name because it's not really semver-correct
+        // https://github.com/steveklabnik/semver/issues/105
+
+        self::runner::parse_command_version(self, command).map(|mut version| {
+            version.pre = semver::Prerelease::EMPTY;
+            version
+        })
+    }
+}
+
+impl Rustc {
+    /// Returns the targets, that `rustc` of the `toolchain` (or the default
+    /// one) can compile for.
+    pub fn target_list(toolchain: Option<&str>) -> Result<Vec<String>> {
+        let mut runner = ExecutableRunner::new(Rustc);
+
+        if let Some(toolchain) = toolchain {
+            runner.with_args([format!("+{toolchain}")]);
+        }
+
+        let output = runner.with_args(["--print", "target-list"]).run()?;
+
+        Ok(output
+            .stdout
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect())
+    }
+
+    /// Checks whether `rustc` of the `toolchain` (or the default one) is a
+    /// nightly or a locally built compiler, which enable unstable features.
+    pub fn is_nightly(toolchain: Option<&str>) -> Result<bool> {
+        let toolchain = toolchain.map(|toolchain| format!("+{toolchain}"));
+
+        let args = match &toolchain {
+            Some(toolchain) => vec![toolchain.as_str(), "-V"],
+            None => vec!["-V"],
+        };
+
+        let version = self::runner::parse_executable_version_with(&Rustc, &args, r"rustc\s(\S+)")?;
+
+        Ok(version.pre.starts_with("nightly") || version.pre.starts_with("dev"))
+    }
+}
+
+/// `rust-ptx-linker` command, the linker of legacy NVPTX setups.
+pub struct PtxLinker;
+
+impl Executable for PtxLinker {
+    fn get_name(&self) -> String {
+        String::from("rust-ptx-linker")
+    }
+
+    fn get_verification_hint(&self) -> String {
+        String::from("Please install it with `cargo install rust-ptx-linker`")
+    }
+
+    fn get_version_hint(&self) -> String {
+        String::from("Please update it with `cargo install --force rust-ptx-linker`")
+    }
+
+    fn get_required_version(&self) -> Option<VersionReq> {
+        None
+    }
+}
+
+/// `ptxas` command from the CUDA toolkit.
+pub struct Ptxas(pub CudaToolkit);
+
+impl Executable for Ptxas {
+    fn get_name(&self) -> String {
+        String::from("ptxas")
+    }
+
+    fn get_path(&self) -> PathBuf {
+        self.0.get_executable_path("ptxas")
+    }
+
+    fn get_verification_hint(&self) -> String {
+        String::from("Please make sure the CUDA toolkit installation is complete")
+    }
+
+    fn get_version_hint(&self) -> String {
+        String::from("Please update the CUDA toolkit")
+    }
+
+    fn get_required_version(&self) -> Option<VersionReq> {
+        Some(VersionReq::parse(">= 10.0.0").unwrap())
+    }
+
+    fn get_current_version(&self) -> Result<Version> {
+        // `ptxas --version` ends with e.g. "Cuda compilation tools, release 12.2, V12.2.140"
+        self::runner::parse_executable_version_with(self, &["--version"], r"release \S+, V(\S+)")
+    }
+}
+
+/// `fatbinary` command from the CUDA toolkit.
+pub struct Fatbinary(pub CudaToolkit);
+
+impl Executable for Fatbinary {
+    fn get_name(&self) -> String {
+        String::from("fatbinary")
+    }
+
+    fn get_path(&self) -> PathBuf {
+        self.0.get_executable_path("fatbinary")
+    }
+
+    fn get_verification_hint(&self) -> String {
+        String::from("Please make sure the CUDA toolkit installation is complete")
+    }
+
+    fn get_version_hint(&self) -> String {
+        String::from("Please update the CUDA toolkit")
+    }
+
+    fn get_required_version(&self) -> Option<VersionReq> {
+        Some(VersionReq::parse(">= 10.0.0").unwrap())
+    }
+
+    fn get_current_version(&self) -> Result<Version> {
+        // `fatbinary --version` ends with e.g. "Cuda compilation tools, release 12.2, V12.2.140"
+        self::runner::parse_executable_version_with(self, &["--version"], r"release \S+, V(\S+)")
+    }
+}
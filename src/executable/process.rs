@@ -63,6 +63,56 @@ pub fn streaming_output<O: FnMut(&str), E: FnMut(&str)>(
     })
 }
 
+/// Same as [`streaming_output`], but awaits the child on the `tokio` runtime.
+#[cfg(feature = "tokio")]
+pub async fn streaming_output_async<O: FnMut(&str), E: FnMut(&str)>(
+    cmd: &mut tokio::process::Command,
+    mut on_stdout_line: O,
+    mut on_stderr_line: E,
+) -> io::Result<Output> {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::null())
+        .spawn()?;
+
+    let mut out_lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    let mut err_lines = BufReader::new(child.stderr.take().unwrap()).lines();
+
+    let (mut out_done, mut err_done) = (false, false);
+
+    while !(out_done && err_done) {
+        tokio::select! {
+            line = out_lines.next_line(), if !out_done => match line? {
+                Some(line) => {
+                    on_stdout_line(&line);
+                    stdout.extend(line.bytes().chain(Some(b'\n')));
+                }
+                None => out_done = true,
+            },
+
+            line = err_lines.next_line(), if !err_done => match line? {
+                Some(line) => {
+                    on_stderr_line(&line);
+                    stderr.extend(line.bytes().chain(Some(b'\n')));
+                }
+                None => err_done = true,
+            },
+        }
+    }
+
+    Ok(Output {
+        status: child.wait().await?,
+        stdout,
+        stderr,
+    })
+}
+
 #[cfg(unix)]
 mod imp {
     use std::{
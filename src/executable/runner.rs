@@ -1,16 +1,20 @@
-use std::{ffi::OsStr, path::Path, process::Command};
+use std::{ffi::OsStr, path::Path, process::Command, sync::LazyLock, thread, time::Duration};
 
 use regex::Regex;
 use semver::Version;
 
 use crate::error::{BuildErrorKind, Error, Result, ResultExt};
 
+#[cfg(feature = "tokio")]
+use super::process::streaming_output_async;
 use super::{process::streaming_output, Executable};
 
 #[allow(clippy::module_name_repetitions)]
 pub struct ExecutableRunner<Ex: Executable> {
     command: Command,
     executable: Ex,
+    retries: u32,
+    retry_delay: Duration,
 }
 
 #[derive(Debug)]
@@ -19,11 +23,29 @@ pub struct Output {
     pub stderr: String,
 }
 
+impl Output {
+    /// Returns the lines of `stdout`, without line endings and the empty
+    /// line after the trailing newline.
+    #[must_use]
+    pub fn stdout_lines(&self) -> Vec<&str> {
+        self.stdout.lines().collect()
+    }
+
+    /// Returns the lines of `stderr`, without line endings and the empty
+    /// line after the trailing newline.
+    #[must_use]
+    pub fn stderr_lines(&self) -> Vec<&str> {
+        self.stderr.lines().collect()
+    }
+}
+
 impl<Ex: Executable> ExecutableRunner<Ex> {
     pub fn new(executable: Ex) -> Self {
         ExecutableRunner {
-            command: Command::new(executable.get_name()),
+            command: Command::new(executable.get_path()),
             executable,
+            retries: 0,
+            retry_delay: Duration::ZERO,
         }
     }
 
@@ -45,6 +67,24 @@ impl<Ex: Executable> ExecutableRunner<Ex> {
         self
     }
 
+    pub fn with_envs<I, K, V>(&mut self, vars: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.command.envs(vars);
+        self
+    }
+
+    pub fn without_env<K>(&mut self, key: K) -> &mut Self
+    where
+        K: AsRef<OsStr>,
+    {
+        self.command.env_remove(key);
+        self
+    }
+
     pub fn with_cwd<P>(&mut self, path: P) -> &mut Self
     where
         P: AsRef<Path>,
@@ -53,49 +93,142 @@ impl<Ex: Executable> ExecutableRunner<Ex> {
         self
     }
 
+    /// Run the command again up to `count` times, when it fails with a
+    /// transient error, e.g. a network failure.
+    ///
+    /// The `delay` before the first retry doubles with every further one.
+    pub fn with_retries(&mut self, count: u32, delay: Duration) -> &mut Self {
+        self.retries = count;
+        self.retry_delay = delay;
+        self
+    }
+
     pub fn run(&mut self) -> Result<Output> {
+        self.run_live(|_| {}, |_| {})
+    }
+
+    pub fn run_live<O: FnMut(&str), E: FnMut(&str)>(
+        &mut self,
+        on_stdout_line: O,
+        on_stderr_line: E,
+    ) -> Result<Output> {
+        self.run_live_with_reset(on_stdout_line, on_stderr_line, || {})
+    }
+
+    /// Same as [`ExecutableRunner::run_live`], but `on_retry` is called
+    /// before every retry, so that the output lines of the failed attempt
+    /// can be discarded.
+    pub fn run_live_with_reset<O: FnMut(&str), E: FnMut(&str), R: FnMut()>(
+        &mut self,
+        mut on_stdout_line: O,
+        mut on_stderr_line: E,
+        mut on_retry: R,
+    ) -> Result<Output> {
         self.check_version()?;
 
-        let raw_output = {
-            self.command.output().with_context(|| {
-                BuildErrorKind::InternalError(format!(
-                    "Unable to execute command '{}'",
-                    self.executable.get_name()
-                ))
-            })?
-        };
+        for attempt in 0.. {
+            let raw_output =
+                streaming_output(&mut self.command, &mut on_stdout_line, &mut on_stderr_line)
+                    .with_context(|| {
+                        BuildErrorKind::InternalError(format!(
+                            "Unable to execute command '{}'",
+                            self.executable.get_name()
+                        ))
+                    })?;
 
-        let output = Output {
-            stdout: String::from_utf8(raw_output.stdout).context(BuildErrorKind::OtherError)?,
-            stderr: String::from_utf8(raw_output.stderr).context(BuildErrorKind::OtherError)?,
-        };
+            match self.collect_output(raw_output) {
+                Err(error) if self.should_retry(attempt, &error) => {
+                    thread::sleep(self.get_retry_delay(attempt));
+                    on_retry();
+                }
 
-        if raw_output.status.success() {
-            Ok(output)
-        } else {
-            Err(Error::from(BuildErrorKind::CommandFailed {
-                command: self.executable.get_name(),
-                code: raw_output.status.code().unwrap_or(-1),
-                stderr: output.stderr,
-            }))
+                result => return result,
+            }
         }
+
+        unreachable!()
     }
 
-    pub fn run_live<O: FnMut(&str), E: FnMut(&str)>(
+    /// Same as [`ExecutableRunner::run_live`], but the command is awaited on
+    /// the `tokio` runtime.
+    ///
+    /// The runner can't be reused afterwards, as the command is moved out.
+    #[cfg(feature = "tokio")]
+    pub async fn run_live_async<O: FnMut(&str), E: FnMut(&str)>(
         &mut self,
         on_stdout_line: O,
         on_stderr_line: E,
+    ) -> Result<Output> {
+        self.run_live_async_with_reset(on_stdout_line, on_stderr_line, || {})
+            .await
+    }
+
+    /// Same as [`ExecutableRunner::run_live_with_reset`], but the command is
+    /// awaited on the `tokio` runtime.
+    #[cfg(feature = "tokio")]
+    pub async fn run_live_async_with_reset<O: FnMut(&str), E: FnMut(&str), R: FnMut()>(
+        &mut self,
+        mut on_stdout_line: O,
+        mut on_stderr_line: E,
+        mut on_retry: R,
     ) -> Result<Output> {
         self.check_version()?;
 
-        let raw_output = streaming_output(&mut self.command, on_stdout_line, on_stderr_line)
-            .with_context(|| {
-                BuildErrorKind::InternalError(format!(
-                    "Unable to execute command '{}'",
-                    self.executable.get_name()
-                ))
-            })?;
+        let mut command = tokio::process::Command::from(std::mem::replace(
+            &mut self.command,
+            Command::new(self.executable.get_path()),
+        ));
+
+        for attempt in 0.. {
+            let raw_output =
+                streaming_output_async(&mut command, &mut on_stdout_line, &mut on_stderr_line)
+                    .await
+                    .with_context(|| {
+                        BuildErrorKind::InternalError(format!(
+                            "Unable to execute command '{}'",
+                            self.executable.get_name()
+                        ))
+                    })?;
+
+            match self.collect_output(raw_output) {
+                Err(error) if self.should_retry(attempt, &error) => {
+                    tokio::time::sleep(self.get_retry_delay(attempt)).await;
+                    on_retry();
+                }
+
+                result => return result,
+            }
+        }
+
+        unreachable!()
+    }
+
+    /// Returns the delay before the retry after the failed `attempt`, which
+    /// saturates instead of overflowing.
+    fn get_retry_delay(&self, attempt: u32) -> Duration {
+        self.retry_delay
+            .saturating_mul(2u32.checked_pow(attempt).unwrap_or(u32::MAX))
+    }
+
+    /// Checks the failure for known transient errors, while retries are left.
+    fn should_retry(&self, attempt: u32, error: &Error) -> bool {
+        static TRANSIENT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(
+                r"(?i)spurious network error|network failure|failed to download|failed to fetch|timed out|timeout was reached|couldn't resolve host|connection (?:reset|refused)|temporary failure in name resolution",
+            )
+            .expect("Unable to parse regex...")
+        });
+
+        match error.kind() {
+            BuildErrorKind::CommandFailed { stderr, .. } => {
+                attempt < self.retries && TRANSIENT_REGEX.is_match(stderr)
+            }
+
+            _ => false,
+        }
+    }
 
+    fn collect_output(&self, raw_output: std::process::Output) -> Result<Output> {
         let output = Output {
             stdout: String::from_utf8(raw_output.stdout).context(BuildErrorKind::OtherError)?,
             stderr: String::from_utf8(raw_output.stderr).context(BuildErrorKind::OtherError)?,
@@ -113,7 +246,9 @@ impl<Ex: Executable> ExecutableRunner<Ex> {
     }
 
     fn check_version(&self) -> Result<()> {
-        let current = self.executable.get_current_version()?;
+        let current = self
+            .executable
+            .get_current_version_with(self.version_command())?;
         let required = self.executable.get_required_version();
 
         match required {
@@ -129,12 +264,72 @@ impl<Ex: Executable> ExecutableRunner<Ex> {
             _ => Ok(()),
         }
     }
+
+    /// Returns the command to query the version with, which runs in the
+    /// working directory and environment of the actual command, and with
+    /// its `+toolchain` override.
+    fn version_command(&self) -> Command {
+        let mut command = Command::new(self.executable.get_path());
+
+        if let Some(cwd) = self.command.get_current_dir() {
+            command.current_dir(cwd);
+        }
+
+        for (key, value) in self.command.get_envs() {
+            match value {
+                Some(value) => command.env(key, value),
+                None => command.env_remove(key),
+            };
+        }
+
+        let toolchain = self
+            .command
+            .get_args()
+            .next()
+            .filter(|arg| arg.to_string_lossy().starts_with('+'));
+
+        command.args(toolchain);
+        command
+    }
 }
 
 pub(crate) fn parse_executable_version<E: Executable>(executable: &E) -> Result<Version> {
-    let mut command = Command::new(executable.get_name());
+    parse_command_version(executable, Command::new(executable.get_path()))
+}
+
+/// Parses the `-V` output of the executable, that is run by the `command`.
+pub(crate) fn parse_command_version<E: Executable>(
+    executable: &E,
+    command: Command,
+) -> Result<Version> {
+    parse_command_version_with(
+        executable,
+        command,
+        &["-V"],
+        &format!(r"{}\s(\S+)", executable.get_name()),
+    )
+}
+
+pub(crate) fn parse_executable_version_with<E: Executable>(
+    executable: &E,
+    args: &[&str],
+    version_pattern: &str,
+) -> Result<Version> {
+    parse_command_version_with(
+        executable,
+        Command::new(executable.get_path()),
+        args,
+        version_pattern,
+    )
+}
 
-    command.args(["-V"]);
+fn parse_command_version_with<E: Executable>(
+    executable: &E,
+    mut command: Command,
+    args: &[&str],
+    version_pattern: &str,
+) -> Result<Version> {
+    command.args(args);
 
     let raw_output = {
         command
@@ -158,8 +353,7 @@ pub(crate) fn parse_executable_version<E: Executable>(executable: &E) -> Result<
         });
     }
 
-    let version_regex = Regex::new(&format!(r"{}\s(\S+)", executable.get_name()))
-        .context(BuildErrorKind::OtherError)?;
+    let version_regex = Regex::new(version_pattern).context(BuildErrorKind::OtherError)?;
 
     match version_regex.captures(&(output.stdout + &output.stderr)) {
         Some(captures) => Ok(Version::parse(&captures[1]).context(BuildErrorKind::OtherError)?),
@@ -169,3 +363,35 @@ pub(crate) fn parse_executable_version<E: Executable>(executable: &E) -> Result<
         ))),
     }
 }
+
+#[test]
+fn should_split_output_lines() {
+    let output = Output {
+        stdout: String::from("sample_ptx_crate\n"),
+        stderr: String::from("first\r\n\nlast"),
+    };
+
+    assert_eq!(output.stdout_lines(), ["sample_ptx_crate"]);
+    assert_eq!(output.stderr_lines(), ["first", "", "last"]);
+
+    let empty_output = Output {
+        stdout: String::new(),
+        stderr: String::from("\n"),
+    };
+
+    assert_eq!(empty_output.stdout_lines(), Vec::<&str>::new());
+    assert_eq!(empty_output.stderr_lines(), [""]);
+}
+
+#[test]
+fn should_saturate_retry_delay() {
+    let mut runner = ExecutableRunner::new(super::Rustc);
+    runner.with_retries(100, Duration::from_secs(1));
+
+    assert_eq!(runner.get_retry_delay(0), Duration::from_secs(1));
+    assert_eq!(runner.get_retry_delay(3), Duration::from_secs(8));
+    assert_eq!(
+        runner.get_retry_delay(64),
+        Duration::from_secs(u64::from(u32::MAX))
+    );
+}
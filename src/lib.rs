@@ -27,12 +27,20 @@ pub mod builder;
 /// Build reporting helpers.
 pub mod reporter;
 
+mod message;
+mod ptx;
 mod source;
 
 /// Convenient re-exports of mostly used types.
 pub mod prelude {
     pub use crate::{
-        builder::{BuildStatus, Builder, CrateType, MessageFormat, Profile},
+        builder::{
+            link_ptx, BuildEvent, BuildMetadata, BuildReport, BuildStatus, Builder,
+            ComputeCapability, CrateTarget, CrateTargetKind, CrateType, DebugInfo, KernelParam,
+            KernelResourceUsage, KernelSignature, KernelVisibility, LtoMode, MessageFormat,
+            NvptxTarget, OptLevel, OutputKind, PanicStrategy, Profile, PtxMetadata, SkipReason,
+            Verbosity,
+        },
         reporter::{CargoAdapter, ErrorLogPrinter},
     };
 }
@@ -0,0 +1,191 @@
+use std::path::PathBuf;
+
+use semver::Version;
+use serde::Deserialize;
+
+use crate::error::Diagnostic;
+
+/// Message of `cargo --message-format=json` output.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+pub enum CargoMessage {
+    CompilerMessage {
+        message: Diagnostic,
+    },
+
+    CompilerArtifact(Artifact),
+
+    #[serde(other)]
+    Other,
+}
+
+/// Files emitted by `cargo` for a single build target.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct Artifact {
+    pub package_id: String,
+    pub target: ArtifactTarget,
+    pub filenames: Vec<PathBuf>,
+    pub fresh: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct ArtifactTarget {
+    pub name: String,
+    pub kind: Vec<String>,
+    pub crate_types: Vec<String>,
+}
+
+impl CargoMessage {
+    pub fn parse(line: &str) -> Option<Self> {
+        serde_json::from_str(line).ok()
+    }
+}
+
+impl Artifact {
+    /// Returns the artifact of the target with given crate type.
+    pub fn find<'a>(
+        artifacts: &'a [Artifact],
+        target_name: &str,
+        crate_type: &str,
+    ) -> Option<&'a Artifact> {
+        artifacts.iter().find(|artifact| {
+            artifact.target.name.replace('-', "_") == target_name
+                && artifact
+                    .target
+                    .crate_types
+                    .iter()
+                    .any(|ty| ty == crate_type)
+        })
+    }
+
+    /// Returns the package name and version, unless the target was built
+    /// for the host, e.g. a build script.
+    pub fn get_device_package(&self) -> Option<(String, Version)> {
+        let is_host_target = self
+            .target
+            .kind
+            .iter()
+            .any(|kind| kind == "custom-build" || kind == "proc-macro");
+
+        if is_host_target {
+            return None;
+        }
+
+        Self::parse_package_id(&self.package_id)
+    }
+
+    /// Parses both `name version (source)` and `source#name@version` forms.
+    fn parse_package_id(package_id: &str) -> Option<(String, Version)> {
+        let (name, version) = if let Some((source, spec)) = package_id.split_once('#') {
+            if let Some(name_version) = spec.split_once('@') {
+                name_version
+            } else {
+                // The name is omitted, when it matches the last path segment.
+                let path = source.split('?').next()?;
+                (path.rsplit('/').next()?, spec)
+            }
+        } else {
+            let mut parts = package_id.split(' ');
+            (parts.next()?, parts.next()?)
+        };
+
+        Some((name.to_string(), Version::parse(version).ok()?))
+    }
+
+    /// Returns the emitted file with given extension.
+    pub fn find_file(&self, extension: &str) -> Option<&PathBuf> {
+        self.filenames
+            .iter()
+            .find(|path| path.extension().is_some_and(|ext| ext == extension))
+    }
+}
+
+#[test]
+fn should_parse_compiler_message() {
+    use crate::error::{DiagnosticSpan, DiagnosticSpanLine};
+
+    let line = r#"{"reason":"compiler-message","package_id":"faulty-ptx_crate 0.1.0","message":{"rendered":"error[E0425]: cannot find function `external_fn` in this scope\n","$message_type":"diagnostic","children":[],"code":{"code":"E0425","explanation":"An unresolved name was used.\n"},"level":"error","message":"cannot find function `external_fn` in this scope","spans":[{"byte_end":208,"byte_start":197,"column_end":31,"column_start":20,"expansion":null,"file_name":"src/lib.rs","is_primary":true,"label":"not found in this scope","line_end":7,"line_start":7,"suggested_replacement":null,"suggestion_applicability":null,"text":[{"text":"    *y.offset(0) = external_fn(*x.offset(0)) * a;","highlight_start":20,"highlight_end":31}]}]}}"#;
+
+    assert_eq!(
+        CargoMessage::parse(line),
+        Some(CargoMessage::CompilerMessage {
+            message: Diagnostic {
+                level: String::from("error"),
+                message: String::from("cannot find function `external_fn` in this scope"),
+                code: Some(String::from("E0425")),
+                spans: vec![DiagnosticSpan {
+                    file_name: String::from("src/lib.rs"),
+                    line_start: 7,
+                    line_end: 7,
+                    column_start: 20,
+                    column_end: 31,
+                    is_primary: true,
+                    label: Some(String::from("not found in this scope")),
+                    text: vec![DiagnosticSpanLine {
+                        text: String::from("    *y.offset(0) = external_fn(*x.offset(0)) * a;"),
+                        highlight_start: 20,
+                        highlight_end: 31,
+                    }],
+                }],
+                rendered: Some(String::from(
+                    "error[E0425]: cannot find function `external_fn` in this scope\n"
+                )),
+            }
+        })
+    );
+
+    assert_eq!(
+        CargoMessage::parse(r#"{"reason":"build-finished","success":false}"#),
+        Some(CargoMessage::Other)
+    );
+
+    assert_eq!(CargoMessage::parse("   Compiling sample_ptx_crate"), None);
+}
+
+#[test]
+fn should_find_artifact_file() {
+    let line = r#"{"reason":"compiler-artifact","package_id":"sample_ptx_crate 0.1.0","manifest_path":"/sample/Cargo.toml","target":{"kind":["cdylib"],"crate_types":["cdylib"],"name":"sample_ptx_crate","src_path":"/sample/src/lib.rs","edition":"2021","doc":true,"doctest":false,"test":true},"profile":{"opt_level":"3","debuginfo":0,"debug_assertions":false,"overflow_checks":false,"test":false},"features":[],"filenames":["/target/nvptx64-nvidia-cuda/release/sample_ptx_crate.ptx"],"executable":null,"fresh":false}"#;
+
+    let Some(CargoMessage::CompilerArtifact(artifact)) = CargoMessage::parse(line) else {
+        unreachable!("it should parse the artifact message");
+    };
+
+    assert!(!artifact.fresh);
+
+    assert_eq!(
+        artifact.find_file("ptx"),
+        Some(&PathBuf::from(
+            "/target/nvptx64-nvidia-cuda/release/sample_ptx_crate.ptx"
+        ))
+    );
+
+    assert_eq!(artifact.find_file("ll"), None);
+
+    let artifacts = [artifact];
+
+    assert!(Artifact::find(&artifacts, "sample_ptx_crate", "cdylib").is_some());
+    assert!(Artifact::find(&artifacts, "sample_ptx_crate", "bin").is_none());
+    assert!(Artifact::find(&artifacts, "other_crate", "cdylib").is_none());
+}
+
+#[test]
+fn should_parse_package_ids() {
+    let parse = |package_id| Artifact::parse_package_id(package_id);
+
+    assert_eq!(
+        parse("registry+https://github.com/rust-lang/crates.io-index#either@1.19.0"),
+        Some((String::from("either"), Version::new(1, 19, 0)))
+    );
+
+    assert_eq!(
+        parse("path+file:///sample/sample-ptx_crate#0.1.0"),
+        Some((String::from("sample-ptx_crate"), Version::new(0, 1, 0)))
+    );
+
+    assert_eq!(
+        parse("either 1.19.0 (registry+https://github.com/rust-lang/crates.io-index)"),
+        Some((String::from("either"), Version::new(1, 19, 0)))
+    );
+
+    assert_eq!(parse("either"), None);
+}
@@ -0,0 +1,489 @@
+use std::{collections::BTreeMap, sync::LazyLock};
+
+use regex::Regex;
+use similar::TextDiff;
+
+use crate::builder::{KernelParam, KernelResourceUsage, KernelSignature, KernelVisibility};
+
+/// Returns names of all `.entry` kernels in the PTX `assembly`, in source
+/// order.
+pub fn kernel_names(assembly: &str) -> Vec<String> {
+    static ENTRY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^\s*(?:\.(?:visible|extern|weak)\s+)?\.entry\s+([\w$%]+)\s*\(")
+            .expect("Unable to parse regex...")
+    });
+
+    strip_comments(assembly)
+        .lines()
+        .filter_map(|line| ENTRY_REGEX.captures(line))
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// Returns names of all `.entry` kernels in the PTX `assembly` with their
+/// linkage, in source order.
+pub fn kernels_with_visibility(assembly: &str) -> Vec<(String, KernelVisibility)> {
+    static ENTRY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^\s*(?:\.(visible|extern|weak)\s+)?\.entry\s+([\w$%]+)\s*\(")
+            .expect("Unable to parse regex...")
+    });
+
+    strip_comments(assembly)
+        .lines()
+        .filter_map(|line| ENTRY_REGEX.captures(line))
+        .map(|caps| {
+            let visibility = match caps.get(1) {
+                Some(_) => KernelVisibility::Visible,
+                None => KernelVisibility::Local,
+            };
+
+            (caps[2].to_string(), visibility)
+        })
+        .collect()
+}
+
+/// Returns signatures of all `.entry` kernels in the PTX `assembly`, in
+/// source order.
+pub fn kernel_signatures(assembly: &str) -> Vec<KernelSignature> {
+    static ENTRY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?m)^\s*(?:\.(?:visible|extern|weak)\s+)?\.entry\s+([\w$%]+)\s*\(([^)]*)\)")
+            .expect("Unable to parse regex...")
+    });
+
+    static PARAM_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"\.param\s+(?:\.align\s+\d+\s+)?(\.\w+)\s+([\w$%]+)(?:\[(\d+)\])?")
+            .expect("Unable to parse regex...")
+    });
+
+    let assembly = strip_comments(assembly);
+
+    ENTRY_REGEX
+        .captures_iter(&assembly)
+        .map(|caps| KernelSignature {
+            name: caps[1].to_string(),
+            params: PARAM_REGEX
+                .captures_iter(&caps[2])
+                .map(|caps| KernelParam {
+                    name: caps[2].to_string(),
+                    ty: caps[1].to_string(),
+                    array_len: caps.get(3).and_then(|len| len.as_str().parse().ok()),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Combines PTX `modules` into a single module, with the highest `.version`
+/// of them.
+///
+/// The modules must be built for the same `.target` and `.address_size`
+/// and must not define the same symbol twice, otherwise the conflicts are
+/// returned as error messages.
+pub fn link(modules: &[String]) -> std::result::Result<String, Vec<String>> {
+    static HEADER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^\s*\.(version|target|address_size)\s+(.+?)\s*$")
+            .expect("Unable to parse regex...")
+    });
+
+    static DEFINITION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^\s*(?:\.visible\s+)?\.(?:entry|func)\s+(?:\([^)]*\)\s*)?([\w$%]+)")
+            .expect("Unable to parse regex...")
+    });
+
+    let mut version: Option<(u32, u32)> = None;
+    let mut target: Option<String> = None;
+    let mut address_size: Option<String> = None;
+    let mut definitions = BTreeMap::new();
+    let mut bodies = Vec::with_capacity(modules.len());
+    let mut errors = Vec::new();
+
+    for (index, module) in modules.iter().enumerate() {
+        let module = strip_comments(module);
+        let mut body = Vec::new();
+
+        for line in module.lines() {
+            let Some(caps) = HEADER_REGEX.captures(line) else {
+                if let Some(caps) = DEFINITION_REGEX.captures(line) {
+                    if let Some(first) = definitions.insert(caps[1].to_string(), index) {
+                        errors.push(format!(
+                            "`{}` is defined in modules #{} and #{}",
+                            &caps[1],
+                            first + 1,
+                            index + 1
+                        ));
+                    }
+                }
+
+                body.push(line);
+                continue;
+            };
+
+            let value = caps[2].to_string();
+
+            match &caps[1] {
+                "version" => {
+                    let parsed = value
+                        .split_once('.')
+                        .and_then(|(major, minor)| Some((major.parse().ok()?, minor.parse().ok()?)));
+
+                    match parsed {
+                        Some(parsed) => version = version.max(Some(parsed)),
+                        None => errors.push(format!("invalid `.version {value}` in module #{}", index + 1)),
+                    }
+                }
+
+                "target" => match &target {
+                    Some(first) if *first != value => errors.push(format!(
+                        "`.target {value}` of module #{} differs from `.target {first}`",
+                        index + 1
+                    )),
+                    _ => target = Some(value),
+                },
+
+                _ => match &address_size {
+                    Some(first) if *first != value => errors.push(format!(
+                        "`.address_size {value}` of module #{} differs from `.address_size {first}`",
+                        index + 1
+                    )),
+                    _ => address_size = Some(value),
+                },
+            }
+        }
+
+        bodies.push(body.join("\n").trim().to_string());
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let header = [
+        version.map(|(major, minor)| format!(".version {major}.{minor}")),
+        target.map(|target| format!(".target {target}")),
+        address_size.map(|address_size| format!(".address_size {address_size}")),
+    ];
+
+    Ok(format!(
+        "//\n// Linked by ptx-builder\n//\n\n{}\n\n{}\n",
+        header.into_iter().flatten().collect::<Vec<_>>().join("\n"),
+        bodies.join("\n\n")
+    ))
+}
+
+/// Returns Rust source code of a module with the PTX `assembly` and its
+/// kernel names as constants.
+pub fn rust_module(assembly: &str) -> String {
+    // `Debug` formatting escapes quotes and backslashes as in Rust string literals
+    format!(
+        "// Generated by ptx-builder, do not edit.\n\n\
+         /// PTX assembly.\n\
+         pub const PTX: &str = {assembly:?};\n\n\
+         /// Names of the kernels in [`PTX`].\n\
+         pub const KERNELS: &[&str] = &{:?};\n",
+        kernel_names(assembly)
+    )
+}
+
+/// Returns a unified diff from the `baseline` to the `current` PTX assembly,
+/// or `None` when they only differ in comment lines, e.g. the generator
+/// header.
+pub fn diff(baseline: &str, current: &str, names: (&str, &str)) -> Option<String> {
+    let strip = |assembly: &str| {
+        assembly
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("//"))
+            .fold(String::new(), |mut stripped, line| {
+                stripped.push_str(line);
+                stripped.push('\n');
+                stripped
+            })
+    };
+
+    let (baseline, current) = (strip(baseline), strip(current));
+
+    if baseline == current {
+        return None;
+    }
+
+    Some(
+        TextDiff::from_lines(&baseline, &current)
+            .unified_diff()
+            .header(names.0, names.1)
+            .to_string(),
+    )
+}
+
+/// Returns resource usage of all kernels from the `ptxas -v` output.
+pub fn resource_usage(ptxas_output: &str) -> Vec<KernelResourceUsage> {
+    static ENTRY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"Compiling entry function '([^']+)'").expect("Unable to parse regex...")
+    });
+
+    static USAGE_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"Used (\d+) registers").expect("Unable to parse regex..."));
+
+    static MEMORY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(\d+) bytes (lmem|smem|cmem\[\d+\])").expect("Unable to parse regex...")
+    });
+
+    let mut kernels = Vec::new();
+    let mut current_kernel = None;
+
+    for line in ptxas_output.lines() {
+        if let Some(caps) = ENTRY_REGEX.captures(line) {
+            current_kernel = Some(caps[1].to_string());
+            continue;
+        }
+
+        let Some(caps) = USAGE_REGEX.captures(line) else {
+            continue;
+        };
+
+        let Some(name) = current_kernel.take() else {
+            continue;
+        };
+
+        let mut usage = KernelResourceUsage {
+            name,
+            registers: caps[1].parse().unwrap_or_default(),
+            shared_bytes: 0,
+            local_bytes: 0,
+            const_bytes: 0,
+        };
+
+        for caps in MEMORY_REGEX.captures_iter(line) {
+            let bytes: u64 = caps[1].parse().unwrap_or_default();
+
+            match &caps[2] {
+                "lmem" => usage.local_bytes += bytes,
+                "smem" => usage.shared_bytes += bytes,
+                _ => usage.const_bytes += bytes,
+            }
+        }
+
+        kernels.push(usage);
+    }
+
+    kernels
+}
+
+fn strip_comments(assembly: &str) -> String {
+    static BLOCK_COMMENT_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?s)/\*.*?\*/").expect("Unable to parse regex..."));
+
+    BLOCK_COMMENT_REGEX
+        .replace_all(assembly, "")
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn should_find_kernel_names() {
+    let assembly = "
+//
+// Generated by LLVM NVPTX Back-End
+//
+
+.version 6.0
+.target sm_30
+.address_size 64
+
+// .globl the_kernel
+// .visible .entry commented_kernel(
+
+.visible .entry the_kernel(
+    .param .u64 the_kernel_param_0
+)
+{
+    ret;
+}
+
+/*
+.entry block_commented_kernel(
+*/
+
+.entry the_local_kernel(
+    .param .u64 the_local_kernel_param_0
+)
+{
+    ret;
+}
+";
+
+    assert_eq!(kernel_names(assembly), &["the_kernel", "the_local_kernel"]);
+}
+
+#[test]
+fn should_find_kernel_visibilities() {
+    let assembly = "
+.version 6.0
+.target sm_30
+.address_size 64
+
+.func  (.param .b64 func_retval0) the_helper(
+    .param .b64 the_helper_param_0
+)
+{
+    ret;
+}
+
+.visible .func the_visible_helper()
+{
+    ret;
+}
+
+// .entry commented_kernel(
+
+.visible .entry the_kernel(
+    .param .u64 the_kernel_param_0
+)
+{
+    ret;
+}
+
+.entry the_local_kernel()
+{
+    ret;
+}
+";
+
+    assert_eq!(
+        kernels_with_visibility(assembly),
+        &[
+            (String::from("the_kernel"), KernelVisibility::Visible),
+            (String::from("the_local_kernel"), KernelVisibility::Local),
+        ]
+    );
+}
+
+#[test]
+fn should_parse_resource_usage() {
+    let ptxas_output = "
+ptxas info    : 0 bytes gmem
+ptxas info    : Compiling entry function 'the_kernel' for 'sm_61'
+ptxas info    : Function properties for the_kernel
+    0 bytes stack frame, 0 bytes spill stores, 0 bytes spill loads
+ptxas info    : Used 6 registers, 344 bytes cmem[0]
+ptxas info    : Compiling entry function 'the_other_kernel' for 'sm_61'
+ptxas info    : Function properties for the_other_kernel
+    16 bytes stack frame, 0 bytes spill stores, 0 bytes spill loads
+ptxas info    : Used 32 registers, 16 bytes lmem, 1024 bytes smem, 360 bytes cmem[0], 8 bytes cmem[2]
+";
+
+    assert_eq!(
+        resource_usage(ptxas_output),
+        &[
+            KernelResourceUsage {
+                name: String::from("the_kernel"),
+                registers: 6,
+                shared_bytes: 0,
+                local_bytes: 0,
+                const_bytes: 344,
+            },
+            KernelResourceUsage {
+                name: String::from("the_other_kernel"),
+                registers: 32,
+                shared_bytes: 1024,
+                local_bytes: 16,
+                const_bytes: 368,
+            },
+        ]
+    );
+}
+
+#[test]
+fn should_generate_rust_module() {
+    let assembly = ".visible .entry the_kernel(\n)\n{\n\t// \"quoted\" \\ path\n\tret;\n}\n";
+
+    assert_eq!(
+        rust_module(assembly),
+        r#"// Generated by ptx-builder, do not edit.
+
+/// PTX assembly.
+pub const PTX: &str = ".visible .entry the_kernel(\n)\n{\n\t// \"quoted\" \\ path\n\tret;\n}\n";
+
+/// Names of the kernels in [`PTX`].
+pub const KERNELS: &[&str] = &["the_kernel"];
+"#
+    );
+}
+
+#[test]
+fn should_diff_assemblies() {
+    let assembly = "//\n// Generated by LLVM NVPTX Back-End\n//\n\n.version 6.0\n.target sm_30\n\n\
+                    .visible .entry the_kernel()\n{\n\tret;\n}\n";
+
+    let regenerated = assembly.replace("LLVM NVPTX Back-End", "LLVM NVPTX Back-End 19");
+
+    assert_eq!(diff(assembly, &regenerated, ("a.ptx", "b.ptx")), None);
+
+    let mutated = assembly.replace("sm_30", "sm_61");
+
+    assert_eq!(
+        diff(assembly, &mutated, ("a.ptx", "b.ptx")).unwrap(),
+        "--- a.ptx\n+++ b.ptx\n@@ -1,6 +1,6 @@\n \n .version 6.0\n-.target sm_30\n+.target sm_61\n \n \
+         .visible .entry the_kernel()\n {\n"
+    );
+}
+
+#[test]
+fn should_find_kernel_signatures() {
+    let assembly = "
+.version 6.0
+.target sm_30
+.address_size 64
+
+// .visible .entry commented_kernel(
+
+.visible .entry the_kernel(
+    .param .u64 the_kernel_param_0,
+    .param .u64 the_kernel_param_1,
+    .param .f64 the_kernel_param_2
+)
+{
+    ret;
+}
+
+.entry the_struct_kernel(
+    .param .align 8 .b8 the_struct_kernel_param_0[16]
+)
+{
+    ret;
+}
+
+.entry the_empty_kernel()
+{
+    ret;
+}
+";
+
+    let param = |name: &str, ty: &str, array_len| KernelParam {
+        name: name.to_string(),
+        ty: ty.to_string(),
+        array_len,
+    };
+
+    assert_eq!(
+        kernel_signatures(assembly),
+        &[
+            KernelSignature {
+                name: String::from("the_kernel"),
+                params: vec![
+                    param("the_kernel_param_0", ".u64", None),
+                    param("the_kernel_param_1", ".u64", None),
+                    param("the_kernel_param_2", ".f64", None),
+                ],
+            },
+            KernelSignature {
+                name: String::from("the_struct_kernel"),
+                params: vec![param("the_struct_kernel_param_0", ".b8", Some(16))],
+            },
+            KernelSignature {
+                name: String::from("the_empty_kernel"),
+                params: vec![],
+            },
+        ]
+    );
+}
@@ -51,15 +51,15 @@ impl CargoAdapter {
         if let Err(error) = self.build_inner(&builder) {
             eprintln!("{}", ErrorLogPrinter::print(error));
             exit(1);
-        } else {
-            exit(0);
         }
+
+        exit(0);
     }
 
     fn build_inner(&self, builder: &Builder) -> Result<()> {
         match builder.build()? {
             BuildStatus::Success(output) => {
-                let dependencies = output.dependencies()?;
+                let directives = output.rerun_directives()?;
 
                 println!(
                     "cargo:rustc-env={}={}",
@@ -67,15 +67,15 @@ impl CargoAdapter {
                     output.get_assembly_path().display()
                 );
 
-                for path in dependencies {
-                    println!("cargo:rerun-if-changed={}", path.display());
+                for directive in directives {
+                    println!("{directive}");
                 }
             }
 
             BuildStatus::NotNeeded => {
                 println!("cargo:rustc-env={}=/dev/null", self.env_name);
             }
-        };
+        }
 
         Ok(())
     }
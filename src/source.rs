@@ -1,16 +1,127 @@
 use std::{
-    collections::hash_map::DefaultHasher,
+    collections::BTreeMap,
     env, fs,
     hash::{Hash, Hasher},
-    io::{BufReader, Read},
+    io::{self, BufReader, Read},
     path::{Path, PathBuf},
 };
 
+use semver::Version;
+
 use crate::{
-    builder::CrateType,
+    builder::{CrateTarget, CrateTargetKind, CrateType, Profile, PtxMetadata},
     error::{BuildErrorKind, Result, ResultExt},
 };
 
+/// 64-bit FNV-1a hasher, which output doesn't depend on the platform:
+/// integers are hashed as little-endian, `usize` and `isize` are widened to
+/// 64 bits.
+///
+/// The `std` [`Hash`] impls of e.g. strings, paths and enums don't promise
+/// a stable encoding, so the crate and the dependencies are fed in with the
+/// explicit encodings of [`StableHasher::write_bytes`] and friends. The
+/// build settings still use their [`Hash`] impls, so a new Rust version may
+/// move the output directories, which are then just built again.
+///
+/// Crate paths are hashed relative to the workspace, so the output
+/// directories also match for checkouts at different locations.
+pub struct StableHasher(u64);
+
+impl StableHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    pub fn new() -> Self {
+        StableHasher(Self::OFFSET_BASIS)
+    }
+
+    /// Writes the `bytes` prefixed with their length.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_usize(bytes.len());
+        self.write(bytes);
+    }
+
+    /// Writes the UTF-8 bytes of the `value` prefixed with their length.
+    pub fn write_string(&mut self, value: &str) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    /// Writes the `values` prefixed with their count.
+    pub fn write_strings(&mut self, values: &[String]) {
+        self.write_usize(values.len());
+
+        for value in values {
+            self.write_string(value);
+        }
+    }
+
+    /// Writes the components of the `path` as UTF-8 strings, independently
+    /// of the platform path separator.
+    pub fn write_path(&mut self, path: &Path) {
+        self.write_usize(path.components().count());
+
+        for component in path.components() {
+            self.write_string(&component.as_os_str().to_string_lossy());
+        }
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.write(&[value]);
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i16(&mut self, value: i16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_i128(&mut self, value: i128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_i64(value as i64);
+    }
+}
+
 #[derive(Hash, Clone, Debug)]
 pub enum FilePrefix {
     Library(String),
@@ -18,13 +129,19 @@ pub enum FilePrefix {
     Mixed { lib: String, bin: String },
 }
 
-#[derive(Hash, Clone, Debug)]
+#[derive(Clone, Debug)]
 /// Information about CUDA crate.
 pub struct Crate {
     name: String,
+    version: Version,
     path: PathBuf,
+    manifest_path: PathBuf,
+    workspace_root: PathBuf,
     output_file_prefix: String,
     deps_file_prefix: FilePrefix,
+    binaries: Vec<String>,
+    examples: Vec<String>,
+    ptx_metadata: PtxMetadata,
 }
 
 impl Crate {
@@ -48,38 +165,73 @@ impl Crate {
             }
         }
 
-        let cargo_toml: toml::Value = {
-            let mut reader = BufReader::new(
-                fs::File::open(path.join("Cargo.toml")).context(BuildErrorKind::OtherError)?,
-            );
-
-            let mut contents = String::new();
+        Self::analyse_manifest(path.join("Cargo.toml"))
+    }
 
-            reader
-                .read_to_string(&mut contents)
-                .context(BuildErrorKind::OtherError)?;
+    /// Try to load a crate from the `manifest` file and collect needed
+    /// information. The crate root is the directory of the manifest.
+    pub fn analyse_manifest<P: AsRef<Path>>(manifest: P) -> Result<Self> {
+        let manifest_path = {
+            env::current_dir()
+                .context(BuildErrorKind::OtherError)?
+                .join(&manifest)
+        };
 
-            toml::from_str(&contents).context(BuildErrorKind::OtherError)?
+        let path = match manifest_path.parent() {
+            Some(parent) if manifest_path.is_file() => parent.to_path_buf(),
+            _ => bail!(BuildErrorKind::InvalidCratePath(manifest_path)),
         };
 
-        let Some(cargo_toml_name) = cargo_toml["package"]["name"].as_str() else {
+        let cargo_toml = Self::read_manifest_file(&manifest_path)?;
+
+        let Some(cargo_toml_name) = Self::get_package_name(&cargo_toml) else {
+            if cargo_toml.get("workspace").is_some() {
+                bail!(BuildErrorKind::WorkspaceMemberRequired(
+                    Self::get_workspace_members(&path, &cargo_toml)?
+                        .into_iter()
+                        .map(|(name, _)| name)
+                        .collect()
+                ));
+            }
+
             bail!(BuildErrorKind::InternalError(String::from(
                 "Cannot get crate name"
             )));
         };
 
-        let is_library = path.join("src").join("lib.rs").exists();
-        let is_binary = path.join("src").join("main.rs").exists();
+        let targets = Self::get_crate_targets(&path, &cargo_toml, &cargo_toml_name)?;
 
-        let output_file_prefix = cargo_toml_name.replace('-', "_");
+        let names = |kind| {
+            targets
+                .iter()
+                .filter(|target| target.kind == kind)
+                .map(|target| target.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let binaries = names(CrateTargetKind::Binary);
+        let examples = names(CrateTargetKind::Example);
+
+        let is_library = targets
+            .iter()
+            .any(|target| target.kind == CrateTargetKind::Library);
+        let has_main = path.join("src").join("main.rs").exists();
+        let is_binary = !binaries.is_empty();
+
+        let output_file_prefix = Self::get_file_prefix(&cargo_toml_name)?;
+
+        let default_binary = match binaries.first() {
+            Some(first) if !has_main => first.clone(),
+            _ => cargo_toml_name.clone(),
+        };
 
         let deps_file_prefix = match (is_binary, is_library) {
             (false, true) => FilePrefix::Library(format!("lib{output_file_prefix}")),
-            (true, false) => FilePrefix::Binary(cargo_toml_name.to_string()),
+            (true, false) => FilePrefix::Binary(default_binary),
 
             (true, true) => FilePrefix::Mixed {
                 lib: format!("lib{output_file_prefix}"),
-                bin: cargo_toml_name.to_string(),
+                bin: default_binary,
             },
 
             (false, false) => {
@@ -89,14 +241,71 @@ impl Crate {
             }
         };
 
+        let workspace_root = Self::get_workspace_root(&path, &cargo_toml);
+        let ptx_metadata = Self::get_package_ptx_metadata(&cargo_toml)?;
+
         Ok(Crate {
-            name: cargo_toml_name.to_string(),
+            name: cargo_toml_name,
+            version: Self::get_package_version(&path, &cargo_toml)?,
             path,
+            manifest_path,
+            workspace_root,
             output_file_prefix,
             deps_file_prefix,
+            binaries,
+            examples,
+            ptx_metadata,
         })
     }
 
+    /// Walk up from the `path` to the nearest crate with `abi_ptx` kernels
+    /// and collect needed information.
+    pub fn locate<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = {
+            env::current_dir()
+                .context(BuildErrorKind::OtherError)?
+                .join(&path)
+        };
+
+        path.ancestors()
+            .find(|dir| dir.join("Cargo.toml").is_file() && Self::has_ptx_kernels(dir))
+            .map_or_else(
+                || Err(BuildErrorKind::InvalidCratePath(path.clone()).into()),
+                Self::analyse,
+            )
+    }
+
+    /// Try to locate the `package` crate inside the workspace at the `path`
+    /// and collect needed information.
+    pub fn analyse_workspace_member<P: AsRef<Path>>(path: P, package: &str) -> Result<Self> {
+        let path = {
+            env::current_dir()
+                .context(BuildErrorKind::OtherError)?
+                .join(&path)
+        };
+
+        if !path.join("Cargo.toml").is_file() {
+            bail!(BuildErrorKind::InvalidCratePath(path.clone()));
+        }
+
+        let cargo_toml = Self::read_manifest(&path)?;
+
+        let root_name = Self::get_package_name(&cargo_toml);
+
+        if root_name.as_deref() == Some(package) {
+            return Self::analyse(path);
+        }
+
+        let members = Self::get_workspace_members(&path, &cargo_toml)?;
+
+        match members.iter().find(|(name, _)| name == package) {
+            Some((_, member_path)) => Self::analyse(member_path),
+            None => bail!(BuildErrorKind::WorkspaceMemberRequired(
+                members.into_iter().map(|(name, _)| name).collect()
+            )),
+        }
+    }
+
     /// Returns PTX assmbly filename prefix.
     pub fn get_output_file_prefix(&self) -> &str {
         &self.output_file_prefix
@@ -105,11 +314,13 @@ impl Crate {
     /// Returns deps file filename prefix.
     pub fn get_deps_file_prefix(&self, crate_type: Option<CrateType>) -> Result<String> {
         match (&self.deps_file_prefix, crate_type) {
-            (FilePrefix::Library(prefix), Some(CrateType::Library) | None)
-            | (FilePrefix::Binary(prefix), Some(CrateType::Binary) | None) => Ok(prefix.clone()),
+            (FilePrefix::Library(prefix), Some(CrateType::Library | CrateType::Mixed) | None)
+            | (FilePrefix::Binary(prefix), Some(CrateType::Binary | CrateType::Mixed) | None) => {
+                Ok(prefix.clone())
+            }
             (FilePrefix::Mixed { bin, .. }, Some(CrateType::Binary)) => Ok(bin.clone()),
             (FilePrefix::Mixed { lib, .. }, Some(CrateType::Library)) => Ok(lib.clone()),
-            (FilePrefix::Mixed { .. }, None) => {
+            (FilePrefix::Mixed { .. }, Some(CrateType::Mixed) | None) => {
                 bail!(BuildErrorKind::MissingCrateType);
             }
 
@@ -124,15 +335,40 @@ impl Crate {
     }
 
     /// Returns the crate type to build the PTX with
-    pub fn get_crate_type(&self, crate_type: Option<CrateType>) -> Result<&str> {
+    ///
+    /// A named `binary` must be one of the crate binary targets.
+    pub fn get_crate_type(
+        &self,
+        crate_type: Option<CrateType>,
+        binary: Option<&str>,
+    ) -> Result<&str> {
+        if let Some(binary) = binary {
+            if matches!(crate_type, Some(CrateType::Library)) {
+                bail!(BuildErrorKind::InvalidCrateType("Library".into()));
+            }
+
+            if self.binaries.is_empty() {
+                bail!(BuildErrorKind::InvalidCrateType("Binary".into()));
+            }
+
+            if !self.binaries.iter().any(|name| name == binary) {
+                bail!(BuildErrorKind::BinaryNotFound {
+                    name: binary.to_string(),
+                    binaries: self.binaries.clone(),
+                });
+            }
+
+            return Ok("bin");
+        }
+
         match (&self.deps_file_prefix, crate_type) {
-            (FilePrefix::Library(_), Some(CrateType::Library) | None)
+            (FilePrefix::Library(_), Some(CrateType::Library | CrateType::Mixed) | None)
             | (FilePrefix::Mixed { .. }, Some(CrateType::Library)) => Ok("cdylib,rlib"),
 
-            (FilePrefix::Binary(_), Some(CrateType::Binary) | None)
+            (FilePrefix::Binary(_), Some(CrateType::Binary | CrateType::Mixed) | None)
             | (FilePrefix::Mixed { .. }, Some(CrateType::Binary)) => Ok("bin"),
 
-            (FilePrefix::Mixed { .. }, None) => {
+            (FilePrefix::Mixed { .. }, Some(CrateType::Mixed) | None) => {
                 bail!(BuildErrorKind::MissingCrateType);
             }
 
@@ -146,33 +382,521 @@ impl Crate {
         }
     }
 
+    /// Returns the crate type to build the PTX `example` with.
+    pub fn get_example_crate_type(&self, example: &str) -> Result<&str> {
+        if !self.examples.iter().any(|name| name == example) {
+            bail!(BuildErrorKind::ExampleNotFound {
+                name: example.to_string(),
+                examples: self.examples.clone(),
+            });
+        }
+
+        Ok("cdylib")
+    }
+
+    /// Returns the library, binary and example targets, like `cargo`
+    /// discovers them.
+    pub fn targets(&self) -> Result<Vec<CrateTarget>> {
+        let cargo_toml = Self::read_manifest_file(&self.manifest_path)?;
+
+        Self::get_crate_targets(&self.path, &cargo_toml, &self.name)
+    }
+
+    /// Returns the build defaults from `[package.metadata.ptx]`.
+    pub fn get_ptx_metadata(&self) -> &PtxMetadata {
+        &self.ptx_metadata
+    }
+
     /// Returns crate name.
     pub fn get_name(&self) -> &str {
         &self.name
     }
 
+    /// Returns crate version.
+    pub fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    /// Returns the `Cargo.lock` of the crate or its workspace, if it exists.
+    pub fn get_lockfile_path(&self) -> Option<PathBuf> {
+        Some(self.workspace_root.join("Cargo.lock")).filter(|path| path.is_file())
+    }
+
     /// Returns crate root path.
     pub fn get_path(&self) -> &Path {
         self.path.as_path()
     }
 
+    /// Returns crate manifest path.
+    pub fn get_manifest_path(&self) -> &Path {
+        self.manifest_path.as_path()
+    }
+
+    /// Returns whether the manifest is not the `Cargo.toml` at the crate root.
+    pub fn has_custom_manifest(&self) -> bool {
+        self.manifest_path != self.path.join("Cargo.toml")
+    }
+
+    /// Returns the common location of all crate builds.
+    ///
+    /// The location is inside of `target_dir`, or the `OUT_DIR` by default.
+    pub fn get_output_root(&self, target_dir: Option<&Path>) -> PathBuf {
+        target_dir
+            .map_or_else(|| PathBuf::from(env!("OUT_DIR")), Path::to_path_buf)
+            .join(&self.output_file_prefix)
+    }
+
     /// Returns temporary crate build location that can be `cargo clean`ed.
-    pub fn get_output_path(&self) -> Result<PathBuf> {
-        let mut path = PathBuf::from(env!("OUT_DIR"));
+    ///
+    /// The `build_config` is hashed alongside the crate, so that different
+    /// build settings do not share the same location.
+    pub fn get_output_path<H: Hash>(
+        &self,
+        target_dir: Option<&Path>,
+        build_config: &H,
+    ) -> Result<PathBuf> {
+        let path = self.get_output_location(target_dir, build_config);
+
+        match fs::create_dir_all(&path) {
+            Ok(()) => Ok(path),
+
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem
+                ) =>
+            {
+                Err(error).context(BuildErrorKind::OutputDirNotWritable { path })
+            }
+
+            Err(error) => Err(error).context(BuildErrorKind::OtherError),
+        }
+    }
+
+    /// Returns the location of [`get_output_path`](#method.get_output_path)
+    /// without creating it.
+    pub fn get_output_location<H: Hash>(
+        &self,
+        target_dir: Option<&Path>,
+        build_config: &H,
+    ) -> PathBuf {
+        self.get_output_root(target_dir)
+            .join(format!("{:x}", self.get_hash(build_config)))
+    }
+
+    /// Returns the existing output directories of all cached builds, that
+    /// were created by [`get_output_path`](#method.get_output_path).
+    pub fn cached_builds(&self, target_dir: Option<&Path>) -> Result<Vec<PathBuf>> {
+        let output_root = self.get_output_root(target_dir);
+
+        if !output_root.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut cached_builds = Vec::new();
+
+        for entry in fs::read_dir(output_root).context(BuildErrorKind::OtherError)? {
+            let entry_path = entry.context(BuildErrorKind::OtherError)?.path();
+
+            if entry_path.is_dir() {
+                cached_builds.push(entry_path);
+            }
+        }
+
+        cached_builds.sort();
+        Ok(cached_builds)
+    }
+
+    /// Checks whether the crate root enables the `abi_ptx` feature.
+    fn has_ptx_kernels(path: &Path) -> bool {
+        ["src/lib.rs", "src/main.rs"].iter().any(|root| {
+            fs::read_to_string(path.join(root)).is_ok_and(|source| source.contains("abi_ptx"))
+        })
+    }
+
+    fn read_manifest(path: &Path) -> Result<toml::Value> {
+        Self::read_manifest_file(&path.join("Cargo.toml"))
+    }
+
+    fn read_manifest_file(manifest: &Path) -> Result<toml::Value> {
+        let mut reader =
+            BufReader::new(fs::File::open(manifest).context(BuildErrorKind::OtherError)?);
+
+        let mut contents = String::new();
+
+        reader
+            .read_to_string(&mut contents)
+            .context(BuildErrorKind::OtherError)?;
+
+        toml::from_str(&contents).context(BuildErrorKind::OtherError)
+    }
+
+    /// Returns `package.name`, which can't be inherited from the workspace.
+    fn get_package_name(cargo_toml: &toml::Value) -> Option<String> {
+        cargo_toml
+            .get("package")
+            .and_then(|package| package.get("name"))
+            .and_then(toml::Value::as_str)
+            .map(String::from)
+    }
+
+    /// Returns `package.version`, which defaults to `0.0.0` like in Cargo.
+    fn get_package_version(path: &Path, cargo_toml: &toml::Value) -> Result<Version> {
+        match Self::get_package_value(path, cargo_toml, "version")? {
+            Some(version) => Version::parse(&version).context(BuildErrorKind::OtherError),
+            None => Ok(Version::new(0, 0, 0)),
+        }
+    }
 
-        path.push(&self.output_file_prefix);
-        path.push(format!("{:x}", self.get_hash()));
+    /// Returns the string `key` of the `package` section, resolving
+    /// `key.workspace = true` from the enclosing workspace.
+    fn get_package_value(
+        path: &Path,
+        cargo_toml: &toml::Value,
+        key: &str,
+    ) -> Result<Option<String>> {
+        let Some(value) = cargo_toml
+            .get("package")
+            .and_then(|package| package.get(key))
+        else {
+            return Ok(None);
+        };
+
+        if let Some(value) = value.as_str() {
+            return Ok(Some(value.to_string()));
+        }
+
+        let is_inherited = value
+            .get("workspace")
+            .and_then(toml::Value::as_bool)
+            .unwrap_or_default();
+
+        if !is_inherited {
+            return Ok(None);
+        }
+
+        for workspace_path in path.ancestors() {
+            if !workspace_path.join("Cargo.toml").is_file() {
+                continue;
+            }
+
+            let workspace_toml = Self::read_manifest(workspace_path)?;
+
+            if let Some(workspace) = workspace_toml.get("workspace") {
+                return Ok(workspace
+                    .get("package")
+                    .and_then(|package| package.get(key))
+                    .and_then(toml::Value::as_str)
+                    .map(String::from));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the directory of the workspace the crate belongs to, which
+    /// is the crate itself for standalone crates.
+    fn get_workspace_root(path: &Path, cargo_toml: &toml::Value) -> PathBuf {
+        if cargo_toml.get("workspace").is_some() {
+            return path.to_path_buf();
+        }
+
+        // Traverse the workspace directory structure towards the root
+        for workspace_path in path.ancestors().skip(1) {
+            if !workspace_path.join("Cargo.toml").is_file() {
+                continue;
+            }
+
+            // Unrelated manifests in parent directories are not validated
+            let is_workspace = Self::read_manifest(workspace_path)
+                .is_ok_and(|manifest| manifest.get("workspace").is_some());
+
+            if is_workspace {
+                return workspace_path.to_path_buf();
+            }
+        }
+
+        path.to_path_buf()
+    }
+
+    /// Returns the crate name that `cargo` passes to `rustc` for the
+    /// package `name`, i.e. with `-` replaced by `_`.
+    fn get_file_prefix(name: &str) -> Result<String> {
+        let is_valid = name.chars().any(char::is_alphanumeric)
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
 
-        fs::create_dir_all(&path).context(BuildErrorKind::OtherError)?;
-        Ok(path)
+        if !is_valid {
+            bail!(BuildErrorKind::InvalidCrateName(name.to_string()));
+        }
+
+        Ok(name.replace('-', "_"))
     }
 
-    fn get_hash(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.hash(&mut hasher);
+    fn get_workspace_members(
+        path: &Path,
+        cargo_toml: &toml::Value,
+    ) -> Result<Vec<(String, PathBuf)>> {
+        let patterns = cargo_toml
+            .get("workspace")
+            .and_then(|workspace| workspace.get("members"))
+            .and_then(toml::Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let mut member_paths = Vec::new();
+
+        for pattern in patterns.iter().filter_map(toml::Value::as_str) {
+            // Only trailing `*` globs, e.g. `crates/*`, are supported
+            if let Some(parent) = pattern.strip_suffix("/*") {
+                for entry in fs::read_dir(path.join(parent)).context(BuildErrorKind::OtherError)? {
+                    member_paths.push(entry.context(BuildErrorKind::OtherError)?.path());
+                }
+            } else {
+                member_paths.push(path.join(pattern));
+            }
+        }
+
+        member_paths.sort();
+
+        let mut members = Vec::new();
+
+        for member_path in member_paths {
+            if !member_path.join("Cargo.toml").is_file() {
+                continue;
+            }
+
+            let member_toml = Self::read_manifest(&member_path)?;
+
+            if let Some(name) = Self::get_package_name(&member_toml) {
+                members.push((name, member_path));
+            }
+        }
+
+        Ok(members)
+    }
+
+    /// Parses the `[package.metadata.ptx]` table.
+    fn get_package_ptx_metadata(cargo_toml: &toml::Value) -> Result<PtxMetadata> {
+        let Some(table) = cargo_toml
+            .get("package")
+            .and_then(|package| package.get("metadata"))
+            .and_then(|metadata| metadata.get("ptx"))
+        else {
+            return Ok(PtxMetadata::default());
+        };
+
+        let strings = |key: &str| {
+            table
+                .get(key)
+                .and_then(toml::Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .map(|value| match value.as_str() {
+                    Some(value) => Ok(String::from(value)),
+                    None => Err(value.to_string()),
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()
+        };
+
+        let compute_capabilities = strings("compute-capabilities")
+            .map_err(BuildErrorKind::InvalidComputeCapability)?
+            .iter()
+            .map(|compute_capability| compute_capability.parse())
+            .collect::<Result<_>>()?;
+
+        let profile =
+            table
+                .get("profile")
+                .and_then(toml::Value::as_str)
+                .map(|profile| match profile {
+                    "dev" | "debug" => Profile::Debug,
+                    "release" => Profile::Release,
+                    name => Profile::Custom(String::from(name)),
+                });
+
+        let features = strings("features").map_err(|feature| {
+            BuildErrorKind::InternalError(format!("Invalid PTX metadata feature: {feature}"))
+        })?;
+
+        Ok(PtxMetadata {
+            compute_capabilities,
+            profile,
+            features,
+        })
+    }
+
+    /// Collects the library, binary and example targets from the manifest
+    /// sections and the automatically discovered sources.
+    fn get_crate_targets(
+        path: &Path,
+        cargo_toml: &toml::Value,
+        package: &str,
+    ) -> Result<Vec<CrateTarget>> {
+        let lib_section = cargo_toml.get("lib");
+
+        let lib_path = lib_section
+            .and_then(|lib| lib.get("path"))
+            .and_then(toml::Value::as_str)
+            .map_or_else(|| Path::new("src").join("lib.rs"), PathBuf::from);
+
+        let mut targets = Vec::new();
+
+        if path.join(&lib_path).is_file() {
+            let name = lib_section
+                .and_then(|lib| lib.get("name"))
+                .and_then(toml::Value::as_str)
+                .map_or_else(
+                    || Self::get_file_prefix(package),
+                    |name| Ok(String::from(name)),
+                )?;
+
+            targets.push(CrateTarget {
+                name,
+                kind: CrateTargetKind::Library,
+                path: lib_path,
+            });
+        }
+
+        let bin_dir = Path::new("src").join("bin");
+        let main_path = Path::new("src").join("main.rs");
+
+        let mut binaries = Self::get_targets(path, cargo_toml, "bin", &bin_dir)?;
+
+        if Self::is_auto_discovered(cargo_toml, "autobins") && path.join(&main_path).is_file() {
+            binaries.entry(package.to_string()).or_insert(main_path);
+        }
+
+        targets.extend(binaries.into_iter().map(|(name, path)| CrateTarget {
+            name,
+            kind: CrateTargetKind::Binary,
+            path,
+        }));
+
+        let examples = Self::get_targets(path, cargo_toml, "example", Path::new("examples"))?;
+
+        targets.extend(examples.into_iter().map(|(name, path)| CrateTarget {
+            name,
+            kind: CrateTargetKind::Example,
+            path,
+        }));
+
+        Ok(targets)
+    }
+
+    /// Collects target names and paths from the `kind` manifest sections and
+    /// the automatically discovered sources in `dir`.
+    fn get_targets(
+        path: &Path,
+        cargo_toml: &toml::Value,
+        kind: &str,
+        dir: &Path,
+    ) -> Result<BTreeMap<String, PathBuf>> {
+        let mut targets = BTreeMap::new();
+
+        if Self::is_auto_discovered(cargo_toml, &format!("auto{kind}s")) && path.join(dir).is_dir()
+        {
+            for entry in fs::read_dir(path.join(dir)).context(BuildErrorKind::OtherError)? {
+                let entry_path = entry.context(BuildErrorKind::OtherError)?.path();
+
+                let target_path = match entry_path.extension() {
+                    Some(ext) if ext == "rs" => entry_path.clone(),
+                    _ => entry_path.join("main.rs"),
+                };
+
+                if let (true, Some(stem)) = (target_path.is_file(), entry_path.file_stem()) {
+                    targets.insert(
+                        stem.to_string_lossy().into_owned(),
+                        target_path
+                            .strip_prefix(path)
+                            .unwrap_or(&target_path)
+                            .to_path_buf(),
+                    );
+                }
+            }
+        }
+
+        let sections = cargo_toml
+            .get(kind)
+            .and_then(toml::Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        for section in sections {
+            let Some(name) = section.get("name").and_then(toml::Value::as_str) else {
+                continue;
+            };
+
+            let target_path = match section.get("path").and_then(toml::Value::as_str) {
+                Some(target_path) => PathBuf::from(target_path),
+                None => targets
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| dir.join(format!("{name}.rs"))),
+            };
+
+            targets.insert(name.to_string(), target_path);
+        }
+
+        Ok(targets)
+    }
+
+    /// Checks the `package.<key>` flag, e.g. `autobins`, that is enabled
+    /// by default.
+    fn is_auto_discovered(cargo_toml: &toml::Value, key: &str) -> bool {
+        cargo_toml
+            .get("package")
+            .and_then(|package| package.get(key))
+            .and_then(toml::Value::as_bool)
+            .unwrap_or(true)
+    }
+
+    fn get_hash<H: Hash>(&self, build_config: &H) -> u64 {
+        let mut hasher = StableHasher::new();
+        self.write_to(&mut hasher);
+        build_config.hash(&mut hasher);
 
         hasher.finish()
     }
+
+    /// Feeds the crate into the `hasher` with explicit encodings.
+    fn write_to(&self, hasher: &mut StableHasher) {
+        // The metadata only provides builder defaults, that are hashed as
+        // a part of the build config.
+        hasher.write_string(&self.name);
+        hasher.write_string(&self.version.to_string());
+
+        // Paths are relative to the workspace, so that the hash stays the
+        // same for checkouts at different locations.
+        for path in [&self.path, &self.manifest_path] {
+            hasher.write_path(path.strip_prefix(&self.workspace_root).unwrap_or(path));
+        }
+
+        hasher.write_string(&self.output_file_prefix);
+
+        match &self.deps_file_prefix {
+            FilePrefix::Library(prefix) => {
+                hasher.write_u8(0);
+                hasher.write_string(prefix);
+            }
+
+            FilePrefix::Binary(prefix) => {
+                hasher.write_u8(1);
+                hasher.write_string(prefix);
+            }
+
+            FilePrefix::Mixed { lib, bin } => {
+                hasher.write_u8(2);
+                hasher.write_string(lib);
+                hasher.write_string(bin);
+            }
+        }
+
+        hasher.write_strings(&self.binaries);
+        hasher.write_strings(&self.examples);
+    }
 }
 
 #[test]
@@ -226,12 +950,163 @@ fn should_find_mixed_crate_names() {
         "libmixed_crate"
     );
 
-    match source.get_deps_file_prefix(None).unwrap_err().kind() {
+    for crate_type in [None, Some(CrateType::Mixed)] {
+        match source.get_deps_file_prefix(crate_type).unwrap_err().kind() {
+            BuildErrorKind::MissingCrateType => {}
+            _ => unreachable!("it should fail with proper error"),
+        }
+    }
+}
+
+#[test]
+fn should_find_custom_binary_targets() {
+    let source = Crate::analyse("tests/fixtures/custom-bin-crate").unwrap();
+
+    assert_eq!(
+        source.targets().unwrap(),
+        vec![
+            CrateTarget {
+                name: "custom_bin_ptx_crate".into(),
+                kind: CrateTargetKind::Library,
+                path: Path::new("src").join("lib.rs"),
+            },
+            CrateTarget {
+                name: "custom-kernel".into(),
+                kind: CrateTargetKind::Binary,
+                path: PathBuf::from("kernels/custom.rs"),
+            },
+        ]
+    );
+
+    assert_eq!(
+        source
+            .get_deps_file_prefix(Some(CrateType::Binary))
+            .unwrap(),
+        "custom-kernel"
+    );
+
+    match source.get_crate_type(None, None).unwrap_err().kind() {
         BuildErrorKind::MissingCrateType => {}
         _ => unreachable!("it should fail with proper error"),
     }
 }
 
+#[test]
+fn should_find_workspace_member() {
+    let source = Crate::analyse_workspace_member("tests/fixtures/workspace", "kernel-b").unwrap();
+
+    assert_eq!(source.get_name(), "kernel-b");
+    assert!(source
+        .get_path()
+        .ends_with("tests/fixtures/workspace/kernel-b"));
+}
+
+#[test]
+fn should_require_workspace_member() {
+    match Crate::analyse("tests/fixtures/workspace")
+        .unwrap_err()
+        .kind()
+    {
+        BuildErrorKind::WorkspaceMemberRequired(members) => {
+            assert_eq!(members, &["kernel-a", "kernel-b"]);
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    match Crate::analyse_workspace_member("tests/fixtures/workspace", "kernel-c")
+        .unwrap_err()
+        .kind()
+    {
+        BuildErrorKind::WorkspaceMemberRequired(members) => {
+            assert_eq!(members, &["kernel-a", "kernel-b"]);
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_find_crate_versions() {
+    let source = Crate::analyse("tests/fixtures/sample-crate").unwrap();
+    assert_eq!(source.get_version(), &Version::new(0, 1, 0));
+
+    let source = Crate::analyse("tests/fixtures/inherited-workspace/kernel").unwrap();
+    assert_eq!(source.get_version(), &Version::new(0, 1, 0));
+}
+
+#[test]
+fn should_sanitize_crate_names() {
+    let source = Crate::analyse("tests/fixtures/unicode-crate").unwrap();
+
+    assert_eq!(source.get_name(), "ünïcode-ptx_crate");
+    assert_eq!(source.get_output_file_prefix(), "ünïcode_ptx_crate");
+
+    assert_eq!(
+        source.get_deps_file_prefix(None).unwrap(),
+        "libünïcode_ptx_crate"
+    );
+
+    match Crate::get_file_prefix("-.-").unwrap_err().kind() {
+        BuildErrorKind::InvalidCrateName(name) => {
+            assert_eq!(name, "-.-");
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_find_inheriting_workspace_member() {
+    let source = Crate::analyse("tests/fixtures/inherited-workspace/kernel").unwrap();
+
+    assert_eq!(source.get_name(), "inherited-ptx-crate");
+    assert_eq!(source.get_version(), &Version::new(0, 1, 0));
+
+    let member = Crate::analyse_workspace_member(
+        "tests/fixtures/inherited-workspace",
+        "inherited-ptx-crate",
+    )
+    .unwrap();
+
+    assert!(member
+        .get_path()
+        .ends_with("tests/fixtures/inherited-workspace/kernel"));
+}
+
+#[test]
+fn should_analyse_manifest() {
+    let source = Crate::analyse_manifest("tests/fixtures/sample-crate/Cargo.toml").unwrap();
+
+    assert_eq!(source.get_name(), "sample-ptx_crate");
+    assert!(source.get_path().ends_with("tests/fixtures/sample-crate"));
+    assert!(!source.has_custom_manifest());
+
+    match Crate::analyse_manifest("tests/fixtures/sample-crate")
+        .unwrap_err()
+        .kind()
+    {
+        BuildErrorKind::InvalidCratePath(path) => {
+            assert!(path.ends_with("tests/fixtures/sample-crate"));
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_locate_crate() {
+    let source = Crate::locate("tests/fixtures/sample-crate/src").unwrap();
+
+    assert_eq!(source.get_name(), "sample-ptx_crate");
+    assert!(source.get_path().ends_with("tests/fixtures/sample-crate"));
+
+    match Crate::locate(env::temp_dir()).unwrap_err().kind() {
+        BuildErrorKind::InvalidCratePath(path) => assert_eq!(path, &env::temp_dir()),
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
 #[test]
 fn should_check_existence_of_crate_path() {
     let result = Crate::analyse("tests/fixtures/non-existing-crate");
@@ -258,12 +1133,147 @@ fn should_check_validity_of_crate_path() {
     }
 }
 
+#[test]
+fn should_find_binaries() {
+    let source = Crate::analyse("tests/fixtures/bins-crate").unwrap();
+
+    assert_eq!(source.binaries, &["first-kernel", "second-kernel"]);
+    assert_eq!(
+        source.get_crate_type(None, Some("second-kernel")).unwrap(),
+        "bin"
+    );
+
+    match source
+        .get_crate_type(None, Some("third-kernel"))
+        .unwrap_err()
+        .kind()
+    {
+        BuildErrorKind::BinaryNotFound { name, .. } => {
+            assert_eq!(name, "third-kernel");
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    let source = Crate::analyse("tests/fixtures/mixed-crate").unwrap();
+
+    assert_eq!(source.binaries, &["mixed-crate"]);
+}
+
+#[test]
+fn should_find_examples() {
+    let source = Crate::analyse("tests/fixtures/examples-crate").unwrap();
+
+    assert_eq!(source.examples, &["example-kernel"]);
+    assert_eq!(
+        source.get_example_crate_type("example-kernel").unwrap(),
+        "cdylib"
+    );
+
+    match source
+        .get_example_crate_type("other-kernel")
+        .unwrap_err()
+        .kind()
+    {
+        BuildErrorKind::ExampleNotFound { name, .. } => {
+            assert_eq!(name, "other-kernel");
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
 #[test]
 fn should_provide_output_path() {
     let source_crate = Crate::analyse("tests/fixtures/sample-crate").unwrap();
 
     assert!(source_crate
-        .get_output_path()
+        .get_output_path(None, &())
         .unwrap()
         .starts_with(Path::new(env!("OUT_DIR")).join("sample_ptx_crate")));
+
+    let target_dir = std::env::temp_dir().join("ptx-builder-source-target-dir");
+
+    assert!(source_crate
+        .get_output_path(Some(&target_dir), &())
+        .unwrap()
+        .starts_with(target_dir.join("sample_ptx_crate")));
+}
+
+#[test]
+fn should_list_cached_builds() {
+    let source_crate = Crate::analyse("tests/fixtures/sample-crate").unwrap();
+
+    let target_dir = std::env::temp_dir().join("ptx-builder-cached-builds");
+    fs::remove_dir_all(&target_dir).unwrap_or_default();
+
+    assert_eq!(
+        source_crate.cached_builds(Some(&target_dir)).unwrap(),
+        Vec::<PathBuf>::new()
+    );
+
+    let mut output_paths = vec![
+        source_crate
+            .get_output_path(Some(&target_dir), &"debug")
+            .unwrap(),
+        source_crate
+            .get_output_path(Some(&target_dir), &"release")
+            .unwrap(),
+    ];
+
+    output_paths.sort();
+
+    assert_eq!(
+        source_crate.cached_builds(Some(&target_dir)).unwrap(),
+        output_paths
+    );
+
+    fs::remove_dir_all(&target_dir).unwrap_or_default();
+}
+
+#[test]
+fn should_hash_stably() {
+    let hash = |value: &dyn Fn(&mut StableHasher)| {
+        let mut hasher = StableHasher::new();
+        value(&mut hasher);
+        hasher.finish()
+    };
+
+    // Reference values of the FNV-1a algorithm.
+    assert_eq!(hash(&|_| ()), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(hash(&|hasher| hasher.write(b"a")), 0xaf63_dc4c_8601_ec8c);
+    assert_eq!(
+        hash(&|hasher| hasher.write(b"foobar")),
+        0x8594_4171_f739_67e8
+    );
+
+    assert_eq!(
+        hash(&|hasher| 1_usize.hash(hasher)),
+        hash(&|hasher| 1_u64.hash(hasher))
+    );
+
+    let source_crate = Crate {
+        name: String::from("sample-ptx_crate"),
+        version: Version::new(0, 1, 0),
+        path: PathBuf::from("/sample"),
+        manifest_path: PathBuf::from("/sample/Cargo.toml"),
+        workspace_root: PathBuf::from("/sample"),
+        output_file_prefix: String::from("sample_ptx_crate"),
+        deps_file_prefix: FilePrefix::Library(String::from("libsample_ptx_crate")),
+        binaries: Vec::new(),
+        examples: Vec::new(),
+        ptx_metadata: PtxMetadata::default(),
+    };
+
+    // Only explicitly encoded values, the build config is a plain integer.
+    assert_eq!(source_crate.get_hash(&1_u32), 0x81ad_e3a5_d8ad_015c);
+
+    let moved_crate = Crate {
+        path: PathBuf::from("/elsewhere/sample"),
+        manifest_path: PathBuf::from("/elsewhere/sample/Cargo.toml"),
+        workspace_root: PathBuf::from("/elsewhere/sample"),
+        ..source_crate.clone()
+    };
+
+    assert_eq!(moved_crate.get_hash(&1_u32), source_crate.get_hash(&1_u32));
 }
@@ -1,14 +1,23 @@
 use std::{
     env::{self, current_dir},
-    fs::{remove_dir_all, File},
+    ffi::OsString,
+    fs::{
+        copy, create_dir_all, metadata, read_dir, remove_dir_all, remove_file, set_permissions,
+        write, File, OpenOptions,
+    },
     io::prelude::*,
+    panic,
     path::{Path, PathBuf},
-    sync::LazyLock,
+    process::Command,
+    sync::{Arc, LazyLock},
+    thread,
+    time::{Duration, Instant},
 };
 
 use antidote::Mutex;
+use semver::{Version, VersionReq};
 
-use ptx_builder::{error::*, prelude::*};
+use ptx_builder::{error::*, executable::CudaToolkit, prelude::*};
 
 static ENV_MUTEX: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
 
@@ -32,7 +41,396 @@ fn should_provide_output_path() {
 }
 
 #[test]
-fn should_write_assembly() {
+fn should_build_from_manifest() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::from_manifest("tests/fixtures/sample-crate/Cargo.toml").unwrap();
+
+    assert_eq!(builder.get_crate_name(), "sample-ptx_crate");
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output
+                .get_assembly_path()
+                .starts_with(Path::new(env!("OUT_DIR")).join("sample_ptx_crate")));
+
+            assert!(output
+                .kernel_names()
+                .unwrap()
+                .contains(&String::from("the_kernel")));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_report_missing_cuda_toolkit() {
+    let _lock = ENV_MUTEX.lock();
+
+    let saved_vars = ["CUDA_PATH", "CUDA_HOME", "PATH"].map(|var| (var, env::var_os(var)));
+
+    for (var, _) in &saved_vars {
+        env::remove_var(var);
+    }
+
+    let result = CudaToolkit::locate();
+
+    for (var, value) in saved_vars {
+        if let Some(value) = value {
+            env::set_var(var, value);
+        }
+    }
+
+    match result.unwrap_err().kind() {
+        BuildErrorKind::CudaToolkitNotFound => {}
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_report_unsupported_compute_capability() {
+    let _lock = ENV_MUTEX.lock();
+
+    let saved_cuda_path = env::var_os("CUDA_PATH");
+
+    env::set_var(
+        "CUDA_PATH",
+        Path::new("tests/fixtures/fake-cuda")
+            .canonicalize()
+            .unwrap(),
+    );
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_compute_capability(ComputeCapability::Sm90)
+        .emit_cubin()
+        .disable_colors();
+
+    let result = builder.build();
+
+    match saved_cuda_path {
+        Some(value) => env::set_var("CUDA_PATH", value),
+        None => env::remove_var("CUDA_PATH"),
+    }
+
+    match result.unwrap_err().kind() {
+        BuildErrorKind::UnsupportedComputeCapability {
+            requested,
+            supported,
+        } => {
+            assert_eq!(requested, "sm_90");
+            assert_eq!(supported, &["sm_50", "sm_86"]);
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_accept_path_types() {
+    let crate_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("sample-crate");
+
+    let expected_args = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .get_cargo_args()
+        .unwrap();
+
+    assert_eq!(
+        Builder::new(&crate_path).unwrap().get_cargo_args().unwrap(),
+        expected_args
+    );
+
+    assert_eq!(
+        Builder::new(crate_path.as_path())
+            .unwrap()
+            .get_cargo_args()
+            .unwrap(),
+        expected_args
+    );
+
+    assert_eq!(
+        Builder::new(crate_path).unwrap().get_crate_name(),
+        "sample-ptx_crate"
+    );
+}
+
+#[test]
+fn should_locate_crate() {
+    let _lock = ENV_MUTEX.lock();
+
+    let saved_manifest_dir = env::var_os("CARGO_MANIFEST_DIR");
+
+    env::set_var(
+        "CARGO_MANIFEST_DIR",
+        Path::new("tests/fixtures/sample-crate/src")
+            .canonicalize()
+            .unwrap(),
+    );
+
+    let located = Builder::locate();
+
+    env::set_var("CARGO_MANIFEST_DIR", env::temp_dir());
+
+    let missing = Builder::locate();
+
+    match saved_manifest_dir {
+        Some(value) => env::set_var("CARGO_MANIFEST_DIR", value),
+        None => env::remove_var("CARGO_MANIFEST_DIR"),
+    }
+
+    assert_eq!(
+        located.unwrap().get_cargo_args().unwrap(),
+        Builder::new("tests/fixtures/sample-crate")
+            .unwrap()
+            .get_cargo_args()
+            .unwrap()
+    );
+
+    match missing.unwrap_err().kind() {
+        BuildErrorKind::InvalidCratePath(path) => assert_eq!(path, &env::temp_dir()),
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_validate_assembly() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let saved_cuda_path = env::var_os("CUDA_PATH");
+
+    env::set_var(
+        "CUDA_PATH",
+        Path::new("tests/fixtures/fake-cuda")
+            .canonicalize()
+            .unwrap(),
+    );
+
+    let valid_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .validate_ptx()
+        .disable_colors();
+
+    let malformed_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_ptx_postprocessor(|assembly| Ok(format!("MALFORMED\n{assembly}")))
+        .validate_ptx()
+        .disable_colors();
+
+    let valid_result = valid_builder.build().map(|status| match status {
+        BuildStatus::Success(output) => output.get_assembly_path(),
+        BuildStatus::NotNeeded => unreachable!(),
+    });
+
+    let malformed_result = malformed_builder.build().map(|_| ());
+
+    match saved_cuda_path {
+        Some(value) => env::set_var("CUDA_PATH", value),
+        None => env::remove_var("CUDA_PATH"),
+    }
+
+    assert!(valid_result.unwrap().exists());
+
+    match malformed_result.unwrap_err().kind() {
+        BuildErrorKind::PtxValidationFailed { messages } => {
+            assert_eq!(messages.len(), 2);
+            assert!(messages[0].contains("Unknown directive 'MALFORMED'"));
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_build_relocatable_code() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let saved_cuda_path = env::var_os("CUDA_PATH");
+
+    env::set_var(
+        "CUDA_PATH",
+        Path::new("tests/fixtures/fake-cuda")
+            .canonicalize()
+            .unwrap(),
+    );
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_relocatable(true)
+        .validate_ptx()
+        .disable_colors();
+
+    let default_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .validate_ptx()
+        .disable_colors();
+
+    let result = builder.build().map(|status| match status {
+        BuildStatus::Success(output) => output.kernel_names().unwrap(),
+        BuildStatus::NotNeeded => unreachable!(),
+    });
+
+    match saved_cuda_path {
+        Some(value) => env::set_var("CUDA_PATH", value),
+        None => env::remove_var("CUDA_PATH"),
+    }
+
+    assert_ne!(
+        builder.dry_run().unwrap(),
+        default_builder.dry_run().unwrap()
+    );
+    assert!(result.unwrap().contains(&String::from("the_kernel")));
+}
+
+#[test]
+fn should_provide_dry_run_command() {
+    let _lock = ENV_MUTEX.lock();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_compute_capability(ComputeCapability::Sm70)
+        .with_features(&["feature-a"])
+        .with_rustc_flag("-C no-vectorize-loops")
+        .with_env("PTX_DRY_RUN_MARKER", "1");
+
+    let command = builder.dry_run().unwrap();
+
+    assert_eq!(command[0], "PTX_DRY_RUN_MARKER=1");
+    assert!(command.contains(&String::from("PTX_CRATE_BUILDING=1")));
+
+    let cargo_position = command.iter().position(|arg| arg == "cargo").unwrap();
+
+    assert_eq!(
+        &command[cargo_position + 1..],
+        builder.get_cargo_args().unwrap()
+    );
+
+    for arg in [
+        "rustc",
+        "--release",
+        "nvptx64-nvidia-cuda",
+        "feature-a",
+        "target-cpu=sm_70",
+        "no-vectorize-loops",
+    ] {
+        assert!(command.iter().any(|item| item == arg), "missing `{arg}`");
+    }
+}
+
+#[test]
+fn should_build_into_target_dir() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let target_dir = env::temp_dir().join("ptx-builder-target-dir");
+    remove_dir_all(&target_dir).unwrap_or_default();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_target_dir(&target_dir);
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let assembly_path = output.get_assembly_path();
+
+            assert!(assembly_path.starts_with(target_dir.join("sample_ptx_crate")));
+            assert!(!assembly_path.starts_with(env!("OUT_DIR")));
+            assert!(assembly_path.exists());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+
+    remove_dir_all(&target_dir).unwrap_or_default();
+}
+
+#[test]
+fn should_report_read_only_output_dir() {
+    let _lock = ENV_MUTEX.lock();
+
+    let target_dir = env::temp_dir().join("ptx-builder-read-only-target-dir");
+    remove_dir_all(&target_dir).unwrap_or_default();
+    create_dir_all(&target_dir).unwrap();
+
+    let mut permissions = metadata(&target_dir).unwrap().permissions();
+    permissions.set_readonly(true);
+    set_permissions(&target_dir, permissions.clone()).unwrap();
+
+    // Privileged users can write into read-only directories anyway.
+    if create_dir_all(target_dir.join("probe")).is_err() {
+        let builder = Builder::new("tests/fixtures/sample-crate")
+            .unwrap()
+            .with_target_dir(&target_dir);
+
+        match builder.build().unwrap_err().kind() {
+            BuildErrorKind::OutputDirNotWritable { path } => {
+                assert!(path.starts_with(target_dir.join("sample_ptx_crate")));
+            }
+
+            _ => unreachable!("it should fail with proper error"),
+        }
+    }
+
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    set_permissions(&target_dir, permissions).unwrap();
+
+    remove_dir_all(&target_dir).unwrap_or_default();
+}
+
+#[test]
+fn should_build_concurrently() {
+    // Other tests modify e.g. `PATH`, while the two builds run in parallel.
+    let _lock = ENV_MUTEX.lock();
+
+    let target_dir = env::temp_dir().join("ptx-builder-concurrent-target-dir");
+    remove_dir_all(&target_dir).unwrap_or_default();
+
+    let handles = [ComputeCapability::Sm61, ComputeCapability::Sm75].map(|compute_capability| {
+        let target_dir = target_dir.clone();
+
+        thread::spawn(move || {
+            let builder = Builder::new("tests/fixtures/sample-crate")
+                .unwrap()
+                .with_target_dir(&target_dir)
+                .set_compute_capability(compute_capability)
+                .with_env("PTX_CONCURRENT_BUILD", compute_capability.to_string())
+                .disable_colors();
+
+            match builder.build().unwrap() {
+                BuildStatus::Success(output) => {
+                    assert!(output.get_assembly_path().starts_with(&target_dir));
+                    assert_eq!(output.kernel_names().unwrap(), &["the_kernel"]);
+                }
+
+                BuildStatus::NotNeeded => unreachable!(),
+            }
+        })
+    });
+
+    for handle in handles {
+        if let Err(panic) = handle.join() {
+            panic::resume_unwind(panic);
+        }
+    }
+
+    assert_eq!(env::var_os("PTX_CONCURRENT_BUILD"), None);
+
+    remove_dir_all(&target_dir).unwrap_or_default();
+}
+
+#[test]
+fn should_provide_crate_name_and_version() {
     let _lock = ENV_MUTEX.lock();
 
     cleanup_temp_location();
@@ -41,90 +439,2391 @@ fn should_write_assembly() {
 
     match builder.disable_colors().build().unwrap() {
         BuildStatus::Success(output) => {
-            let mut assembly_contents = String::new();
+            assert_eq!(output.crate_name(), "sample-ptx_crate");
+            assert_eq!(output.crate_version(), &Version::new(0, 1, 0));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_provide_dependency_crates() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let crates = output.dependency_crates().unwrap();
+
+            assert!(crates.iter().any(|(name, version)| name == "either"
+                && VersionReq::parse("1.5").unwrap().matches(version)));
+
+            assert!(!crates.iter().any(|(name, _)| name == "sample-ptx_crate"));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_name_assembly() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let mut assembly_paths = Vec::new();
+
+    for name in ["first-kernels", "second-kernels"] {
+        let builder = Builder::new("tests/fixtures/sample-crate")
+            .unwrap()
+            .with_output_name(name)
+            .disable_colors();
+
+        match builder.build().unwrap() {
+            BuildStatus::Success(output) => {
+                let assembly_path = output.get_assembly_path();
+
+                assert!(assembly_path.ends_with(format!("{name}.ptx")));
+                assert!(
+                    assembly_path.starts_with(Path::new(env!("OUT_DIR")).join("sample_ptx_crate"))
+                );
+
+                assembly_paths.push(assembly_path);
+            }
+
+            BuildStatus::NotNeeded => unreachable!(),
+        }
+    }
+
+    for assembly_path in assembly_paths {
+        assert!(assembly_path.exists());
+    }
+}
+
+#[test]
+fn should_clean_output_path() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors();
+
+    let output_path = match builder.build().unwrap() {
+        BuildStatus::Success(output) => output.get_assembly_path(),
+        BuildStatus::NotNeeded => unreachable!(),
+    };
+
+    assert!(output_path.exists());
+
+    builder.clean().unwrap();
+    assert!(!output_path.exists());
+    assert!(!Path::new(env!("OUT_DIR")).join("sample_ptx_crate").exists());
+
+    builder.clean().unwrap();
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn should_build_asynchronously() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors();
+
+    match builder.build_async().await.unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.get_assembly_path().exists());
+            assert!(output
+                .kernel_names()
+                .unwrap()
+                .contains(&String::from("the_kernel")));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_build_incrementally() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_profile(Profile::Debug)
+        .set_incremental(true)
+        .disable_colors();
+
+    assert!(builder
+        .dry_run()
+        .unwrap()
+        .contains(&String::from("CARGO_INCREMENTAL=1")));
+
+    for _ in 0..2 {
+        match builder.build().unwrap() {
+            BuildStatus::Success(output) => assert!(output.get_assembly_path().exists()),
+            BuildStatus::NotNeeded => unreachable!(),
+        }
+    }
+
+    builder.clean().unwrap();
+}
+
+#[test]
+fn should_keep_intermediates() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .keep_intermediates()
+        .disable_colors();
+
+    let mut notes = Vec::new();
+
+    let assembly_path = match builder.build_live(|_| {}, |line| notes.push(line.to_string())) {
+        Ok(BuildStatus::Success(output)) => output.get_assembly_path(),
+        Ok(BuildStatus::NotNeeded) => unreachable!(),
+        Err(error) => panic!("{error}"),
+    };
+
+    assert!(notes
+        .iter()
+        .any(|line| line.starts_with("note: keeping intermediate build files in")));
+
+    let deps_dir = assembly_path.parent().unwrap().join("deps");
+
+    assert!(read_dir(deps_dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .any(|entry| entry.file_name().to_string_lossy().contains(".rcgu.")));
+
+    builder.clean().unwrap();
+    assert!(!assembly_path.exists());
+}
+
+#[test]
+fn should_clean_without_build() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    builder.clean().unwrap();
+    assert!(!Path::new(env!("OUT_DIR")).join("sample_ptx_crate").exists());
+}
+
+#[test]
+fn should_provide_build_duration() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.build_duration() > Duration::ZERO);
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_report_build_progress() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let builder = {
+        let events = events.clone();
+
+        Builder::new("tests/fixtures/sample-crate")
+            .unwrap()
+            .disable_colors()
+            .with_progress(move |event| events.lock().push(event))
+    };
+
+    builder.build().unwrap();
+
+    let events = events.lock();
+
+    assert_eq!(events.first(), Some(&BuildEvent::Started));
+    assert_eq!(events.last(), Some(&BuildEvent::Finished));
+    assert!(events.contains(&BuildEvent::CompilingCrate(String::from(
+        "sample-ptx_crate"
+    ))));
+}
+
+#[test]
+fn should_write_assembly() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let mut assembly_contents = String::new();
+
+            File::open(output.get_assembly_path())
+                .unwrap()
+                .read_to_string(&mut assembly_contents)
+                .unwrap();
+
+            assert!(output
+                .get_assembly_path()
+                .to_string_lossy()
+                .contains("release"));
+
+            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_copy_assembly() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let dest_dir = Path::new(env!("OUT_DIR")).join("copied-assembly");
+    let dest_path = dest_dir.join("kernels").join("sample.ptx");
+
+    remove_dir_all(&dest_dir).unwrap_or_default();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert_eq!(output.copy_assembly_to(&dest_path).unwrap(), dest_path);
+            assert_eq!(output.copy_assembly_to(&dest_path).unwrap(), dest_path);
+
+            let mut assembly_contents = String::new();
+
+            File::open(&dest_path)
+                .unwrap()
+                .read_to_string(&mut assembly_contents)
+                .unwrap();
+
+            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+
+            assert_eq!(
+                output.copy_assembly_to(&dest_dir).unwrap(),
+                dest_dir.join(output.get_assembly_path().file_name().unwrap())
+            );
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_diff_assembly() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let golden_dir = Path::new(env!("OUT_DIR")).join("golden-assembly");
+    remove_dir_all(&golden_dir).unwrap_or_default();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let golden_path = output.copy_assembly_to(&golden_dir).unwrap();
+
+            assert_eq!(output.diff_assembly(&golden_path).unwrap(), None);
+
+            let assembly = output.get_assembly_string().unwrap();
+            write(
+                &golden_path,
+                assembly.replace("the_kernel", "the_old_kernel"),
+            )
+            .unwrap();
+
+            let diff = output.diff_assembly(&golden_path).unwrap().unwrap();
+
+            assert!(diff.contains("-.visible .entry the_old_kernel("));
+            assert!(diff.contains("+.visible .entry the_kernel("));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+
+    remove_dir_all(&golden_dir).unwrap_or_default();
+}
+
+#[test]
+fn should_provide_assembly_bytes() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let bytes = output.get_assembly_bytes().unwrap();
+
+            assert_eq!(
+                String::from_utf8(bytes).unwrap(),
+                output.get_assembly_string().unwrap()
+            );
+
+            let digest = output.assembly_sha256().unwrap();
+
+            assert_eq!(digest.len(), 64);
+            assert!(digest
+                .chars()
+                .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)));
+            assert_eq!(digest, output.assembly_sha256().unwrap());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_resolve_emitted_assembly_path() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors();
+
+    let first_path = match builder.build().unwrap() {
+        BuildStatus::Success(output) => output.get_assembly_path(),
+        BuildStatus::NotNeeded => unreachable!(),
+    };
+
+    let emitted_files = read_dir(first_path.parent().unwrap())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+
+    assert!(emitted_files.contains(&first_path));
+
+    let second_path = match builder.build().unwrap() {
+        BuildStatus::Success(output) => output.get_assembly_path(),
+        BuildStatus::NotNeeded => unreachable!(),
+    };
+
+    assert_eq!(first_path, second_path);
+}
+
+#[test]
+fn should_provide_assembly_string() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output
+                .get_assembly_string()
+                .unwrap()
+                .contains(".visible .entry the_kernel("));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_write_rust_module() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    let temp_dir = env::temp_dir().join("ptx-builder-rust-module");
+    remove_dir_all(&temp_dir).unwrap_or_default();
+    create_dir_all(&temp_dir).unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            output
+                .write_rust_module(&temp_dir.join("kernels.rs"))
+                .unwrap();
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+
+    write(
+        temp_dir.join("main.rs"),
+        r#"
+mod kernels {
+    include!("kernels.rs");
+}
+
+fn main() {
+    assert_eq!(kernels::KERNELS, &["the_kernel"]);
+    assert!(kernels::PTX.contains(".visible .entry the_kernel("));
+}
+"#,
+    )
+    .unwrap();
+
+    let status = Command::new("rustc")
+        .arg("main.rs")
+        .current_dir(&temp_dir)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    assert!(Command::new(temp_dir.join("main"))
+        .status()
+        .unwrap()
+        .success());
+
+    remove_dir_all(&temp_dir).unwrap_or_default();
+}
+
+#[test]
+fn should_postprocess_assembly() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_ptx_postprocessor(|assembly| {
+            Ok(assembly.replace(
+                "// Generated by LLVM NVPTX Back-End",
+                "// GENERATED BY LLVM NVPTX BACK-END",
+            ))
+        })
+        .disable_colors();
+
+    // The transformation applies to the cached build as well.
+    for _ in 0..2 {
+        match builder.build().unwrap() {
+            BuildStatus::Success(output) => {
+                let assembly = output.get_assembly_string().unwrap();
+
+                assert!(assembly.contains("// GENERATED BY LLVM NVPTX BACK-END"));
+                assert!(!assembly.contains("// Generated by LLVM NVPTX Back-End"));
+                assert!(assembly.contains(".visible .entry the_kernel("));
+            }
+
+            BuildStatus::NotNeeded => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn should_provide_kernel_names() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output
+                .kernel_names()
+                .unwrap()
+                .contains(&String::from("the_kernel")));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_provide_kernel_visibilities() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let kernels = output.kernels_with_visibility().unwrap();
+            let assembly = output.get_assembly_string().unwrap();
+
+            assert!(kernels.contains(&(String::from("the_kernel"), KernelVisibility::Visible)));
+
+            for (name, _) in &kernels {
+                assert!(!assembly.contains(&format!(".func {name}(")));
+            }
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_require_kernels() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/kernelless-crate")
+        .unwrap()
+        .require_kernels()
+        .disable_colors();
+
+    match builder.build().unwrap_err().kind() {
+        BuildErrorKind::NoKernelsFound(path) => {
+            assert!(path.exists());
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    let sample_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .require_kernels()
+        .disable_colors();
+
+    match sample_builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.get_assembly_path().exists());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_provide_kernel_signatures() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let signatures = output.kernel_signatures().unwrap();
+
+            let signature = signatures
+                .iter()
+                .find(|signature| signature.name == "the_kernel")
+                .unwrap();
+
+            let types = signature
+                .params
+                .iter()
+                .map(|param| param.ty.as_str())
+                .collect::<Vec<_>>();
+
+            assert_eq!(types, &[".u64", ".u64", ".f64"]);
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn should_serialize_build_metadata() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let metadata = output.metadata().unwrap();
+            let json = serde_json::to_string(&metadata).unwrap();
+
+            let restored: BuildMetadata = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(restored, metadata);
+            assert_eq!(restored.assembly_path, output.get_assembly_path());
+            assert_eq!(restored.dependencies, output.dependencies().unwrap());
+            assert_eq!(restored.kernels, vec![String::from("the_kernel")]);
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn should_write_build_report() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let report_path = Path::new(env!("OUT_DIR")).join("ptx-report.json");
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            output.write_report(&report_path).unwrap();
+
+            let mut report_contents = String::new();
+
+            File::open(&report_path)
+                .unwrap()
+                .read_to_string(&mut report_contents)
+                .unwrap();
+
+            let report: BuildReport = serde_json::from_str(&report_contents).unwrap();
+
+            assert_eq!(report, output.report().unwrap());
+            assert_eq!(report.crate_name, "sample-ptx_crate");
+            assert_eq!(report.target, "nvptx64-nvidia-cuda");
+            assert!(!report.kernels.is_empty());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_use_manifest_ptx_metadata() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/metadata-crate")
+        .unwrap()
+        .disable_colors();
+
+    assert_eq!(
+        builder.get_ptx_metadata(),
+        &PtxMetadata {
+            compute_capabilities: vec![ComputeCapability::Sm61],
+            profile: Some(Profile::Debug),
+            features: vec![String::from("scaled")],
+        }
+    );
+
+    let cargo_args = builder.get_cargo_args().unwrap();
+
+    assert!(cargo_args.contains(&String::from("target-cpu=sm_61")));
+    assert!(cargo_args.contains(&String::from("scaled")));
+    assert!(!cargo_args.contains(&String::from("--release")));
+
+    let cargo_args = Builder::new("tests/fixtures/metadata-crate")
+        .unwrap()
+        .set_compute_capability(ComputeCapability::Sm75)
+        .set_profile(Profile::Release)
+        .get_cargo_args()
+        .unwrap();
+
+    assert!(cargo_args.contains(&String::from("target-cpu=sm_75")));
+    assert!(cargo_args.contains(&String::from("--release")));
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert_eq!(output.kernel_names().unwrap(), &["the_scaled_kernel"]);
+            assert!(output
+                .get_assembly_path()
+                .to_string_lossy()
+                .contains("debug"));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_build_named_binaries() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    for (bin, kernel) in [
+        ("first-kernel", "the_first_kernel"),
+        ("second-kernel", "the_second_kernel"),
+    ] {
+        let builder = Builder::new("tests/fixtures/bins-crate")
+            .unwrap()
+            .disable_colors()
+            .with_bin(bin);
+
+        match builder.build().unwrap() {
+            BuildStatus::Success(output) => {
+                assert_eq!(output.kernel_names().unwrap(), &[kernel]);
+            }
+
+            BuildStatus::NotNeeded => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn should_build_example() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/examples-crate")
+        .unwrap()
+        .disable_colors()
+        .with_example("example-kernel");
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output
+                .get_assembly_path()
+                .to_string_lossy()
+                .contains("examples"));
+
+            assert_eq!(output.kernel_names().unwrap(), &["the_example_kernel"]);
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_discover_custom_binary_targets() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/custom-bin-crate").unwrap();
+
+    assert_eq!(
+        builder.targets().unwrap(),
+        &[
+            CrateTarget {
+                name: "custom_bin_ptx_crate".into(),
+                kind: CrateTargetKind::Library,
+                path: PathBuf::from("src/lib.rs"),
+            },
+            CrateTarget {
+                name: "custom-kernel".into(),
+                kind: CrateTargetKind::Binary,
+                path: PathBuf::from("kernels/custom.rs"),
+            },
+        ]
+    );
+
+    let builder = builder.disable_colors().with_bin("custom-kernel");
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output
+                .kernel_names()
+                .unwrap()
+                .iter()
+                .any(|name| name == "the_custom_kernel"));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_reject_unknown_binary() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/bins-crate")
+        .unwrap()
+        .with_bin("third-kernel");
+
+    match builder.build().unwrap_err().kind() {
+        BuildErrorKind::BinaryNotFound { name, binaries } => {
+            assert_eq!(name, "third-kernel");
+            assert_eq!(binaries, &["first-kernel", "second-kernel"]);
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_bin("sample-ptx_crate");
+
+    match builder.build().unwrap_err().kind() {
+        BuildErrorKind::InvalidCrateType(kind) => {
+            assert_eq!(kind, "Binary");
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+#[ignore = "requires the CUDA toolkit"]
+fn should_emit_cubin() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_compute_capability(ComputeCapability::Sm70)
+        .emit_cubin();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let cubin_path = output.get_cubin_path().unwrap();
+
+            assert_eq!(cubin_path.extension().unwrap(), "cubin");
+            assert!(cubin_path.exists());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+#[ignore = "requires the CUDA toolkit"]
+fn should_report_resource_usage() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_compute_capability(ComputeCapability::Sm70);
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let usage = output.resource_usage().unwrap();
+
+            assert_eq!(usage.len(), 1);
+            assert_eq!(usage[0].name, "the_kernel");
+            assert!(usage[0].registers > 0);
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+#[ignore = "requires the CUDA toolkit"]
+fn should_emit_fatbin() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_compute_capabilities(&[ComputeCapability::Sm70, ComputeCapability::Sm86]);
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let fatbin_path = output.get_fatbin_path().unwrap();
+
+            assert_eq!(fatbin_path.extension().unwrap(), "fatbin");
+            assert!(fatbin_path.exists());
+            assert!(output.get_assembly_path().exists());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_write_llvm_ir() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_output_type(OutputKind::LlvmIr);
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert_eq!(output.get_assembly_path().extension().unwrap(), "ll");
+
+            assert!(output
+                .get_assembly_string()
+                .unwrap()
+                .contains("target datalayout"));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_build_mixed_crate_in_debug_mode() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/mixed-crate")
+        .unwrap()
+        .set_crate_type(CrateType::Library)
+        .set_profile(Profile::Debug)
+        .disable_colors();
+
+    assert!(!builder
+        .get_cargo_args()
+        .unwrap()
+        .contains(&String::from("--release")));
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            let assembly_path = output.get_assembly_path();
+
+            assert!(assembly_path.exists());
+            assert!(assembly_path
+                .components()
+                .any(|component| component.as_os_str() == "debug"));
+            assert!(!assembly_path
+                .components()
+                .any(|component| component.as_os_str() == "release"));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_build_mixed_crate_lib() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/mixed-crate").unwrap();
+
+    match builder
+        .set_crate_type(CrateType::Library)
+        .disable_colors()
+        .build()
+        .unwrap()
+    {
+        BuildStatus::Success(output) => {
+            let mut assembly_contents = String::new();
+
+            println!("{}", output.get_assembly_path().display());
+
+            File::open(output.get_assembly_path())
+                .unwrap()
+                .read_to_string(&mut assembly_contents)
+                .unwrap();
+
+            assert!(output
+                .get_assembly_path()
+                .to_string_lossy()
+                .contains("release"));
+
+            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_build_with_features() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let default_builder = Builder::new("tests/fixtures/features-crate")
+        .unwrap()
+        .with_features(&[])
+        .disable_colors();
+
+    let extra_builder = Builder::new("tests/fixtures/features-crate")
+        .unwrap()
+        .with_features(&["extra-kernel"])
+        .disable_colors();
+
+    match (
+        default_builder.build().unwrap(),
+        extra_builder.build().unwrap(),
+    ) {
+        (BuildStatus::Success(default_output), BuildStatus::Success(extra_output)) => {
+            assert_ne!(
+                default_output.get_assembly_path(),
+                extra_output.get_assembly_path()
+            );
+
+            let mut default_contents = String::new();
+            let mut extra_contents = String::new();
+
+            File::open(default_output.get_assembly_path())
+                .unwrap()
+                .read_to_string(&mut default_contents)
+                .unwrap();
+
+            File::open(extra_output.get_assembly_path())
+                .unwrap()
+                .read_to_string(&mut extra_contents)
+                .unwrap();
+
+            assert!(default_contents.contains(".visible .entry the_kernel("));
+            assert!(!default_contents.contains(".visible .entry the_extra_kernel("));
+
+            assert!(extra_contents.contains(".visible .entry the_kernel("));
+            assert!(extra_contents.contains(".visible .entry the_extra_kernel("));
+        }
+
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn should_build_without_default_features() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/features-crate")
+        .unwrap()
+        .disable_default_features()
+        .disable_colors();
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            let mut assembly_contents = String::new();
+
+            File::open(output.get_assembly_path())
+                .unwrap()
+                .read_to_string(&mut assembly_contents)
+                .unwrap();
+
+            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+            assert!(!assembly_contents.contains(".visible .entry the_default_kernel("));
+            assert!(!assembly_contents.contains(".visible .entry the_extra_kernel("));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_build_with_all_features() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/features-crate")
+        .unwrap()
+        .with_features(&["default-kernel"])
+        .disable_default_features()
+        .enable_all_features()
+        .disable_colors();
+
+    let mut warnings = Vec::new();
+
+    match builder
+        .build_live(
+            |_line| (),
+            |line| {
+                if line.starts_with("warning: all features are enabled") {
+                    warnings.push(line.to_string());
+                }
+            },
+        )
+        .unwrap()
+    {
+        BuildStatus::Success(output) => {
+            let mut assembly_contents = String::new();
+
+            File::open(output.get_assembly_path())
+                .unwrap()
+                .read_to_string(&mut assembly_contents)
+                .unwrap();
+
+            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+            assert!(assembly_contents.contains(".visible .entry the_default_kernel("));
+            assert!(assembly_contents.contains(".visible .entry the_extra_kernel("));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn should_pass_extra_rustc_flags() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors()
+        .with_rustc_flag("-C debuginfo=0");
+
+    assert!(builder
+        .get_cargo_args()
+        .unwrap()
+        .ends_with(&[String::from("-C"), String::from("debuginfo=0")]));
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.get_assembly_path().exists());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_pass_unstable_flags() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors()
+        .with_unstable_flag("-Z share-generics=off")
+        .with_rustc_flag("-C debuginfo=0");
+
+    let command = builder.dry_run().unwrap();
+    let position = |arg: &str| command.iter().position(|item| item == arg).unwrap();
+
+    assert!(position("--") < position("share-generics=off"));
+    assert_eq!(position("share-generics=off") - 1, position("-Z"));
+    assert!(command.ends_with(&[String::from("-C"), String::from("debuginfo=0")]));
+
+    assert_ne!(
+        command,
+        Builder::new("tests/fixtures/sample-crate")
+            .unwrap()
+            .disable_colors()
+            .with_rustc_flag("-C debuginfo=0")
+            .dry_run()
+            .unwrap()
+    );
+
+    let invalid_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_unstable_flag("-C opt-level=3");
+
+    match invalid_builder.build().unwrap_err().kind() {
+        BuildErrorKind::InvalidUnstableFlag(flag) => {
+            assert_eq!(flag, "-C opt-level=3");
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.get_assembly_path().exists());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_override_rustc_crate_type() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors()
+        .with_rustc_crate_type("cdylib");
+
+    let command = builder.dry_run().unwrap();
+    let position = |arg: &str| command.iter().position(|item| item == arg).unwrap();
+
+    assert!(position("--") < position("--crate-type"));
+    assert_eq!(command[position("--crate-type") + 1], "cdylib");
+
+    let invalid_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_rustc_crate_type("staticlib");
+
+    match invalid_builder.build().unwrap_err().kind() {
+        BuildErrorKind::UnsupportedRustcCrateType { requested, .. } => {
+            assert_eq!(requested, "staticlib");
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.get_assembly_path().exists());
+            assert_eq!(output.kernel_names().unwrap(), &["the_kernel"]);
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_pass_custom_linker() {
+    let _lock = ENV_MUTEX.lock();
+
+    let command = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors()
+        .dry_run()
+        .unwrap();
+
+    assert!(!command.iter().any(|arg| arg.starts_with("linker=")));
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors()
+        .with_linker("/missing/bin/rust-ptx-linker");
+
+    assert!(builder
+        .dry_run()
+        .unwrap()
+        .windows(2)
+        .any(|pair| pair == ["-C", "linker=/missing/bin/rust-ptx-linker"]));
+
+    match builder.build().unwrap_err().kind() {
+        BuildErrorKind::CommandNotFound { command, hint } => {
+            assert_eq!(command, "/missing/bin/rust-ptx-linker");
+            assert!(hint.contains("with_linker"));
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_reject_missing_sysroot() {
+    let _lock = ENV_MUTEX.lock();
+
+    let sysroot = env::temp_dir().join("ptx-builder-missing-sysroot");
+    remove_dir_all(&sysroot).unwrap_or_default();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_sysroot(&sysroot);
+
+    match builder.build().unwrap_err().kind() {
+        BuildErrorKind::InvalidSysroot(path) => {
+            assert_eq!(path, &sysroot);
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_pass_sysroot() {
+    let _lock = ENV_MUTEX.lock();
+
+    let command = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors()
+        .dry_run()
+        .unwrap();
+
+    assert!(!command.iter().any(|arg| arg == "--sysroot"));
+
+    let sysroot = env::temp_dir().join("ptx-builder-sysroot");
+    create_dir_all(&sysroot).unwrap();
+
+    let command = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors()
+        .with_sysroot(&sysroot)
+        .dry_run()
+        .unwrap();
+
+    let position = |arg: &str| command.iter().position(|item| item == arg).unwrap();
+
+    assert!(position("--") < position("--sysroot"));
+    assert_eq!(
+        command[position("--sysroot") + 1],
+        sysroot.display().to_string()
+    );
+
+    remove_dir_all(&sysroot).unwrap_or_default();
+}
+
+#[test]
+fn should_pass_extra_cargo_args() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/locked-crate")
+        .unwrap()
+        .disable_colors()
+        .with_cargo_arg("--locked");
+
+    let args = builder.get_cargo_args().unwrap();
+
+    let position = |arg: &str| args.iter().position(|item| item == arg).unwrap();
+
+    assert_eq!(args[0], "rustc");
+    assert!(position("--release") < position("--locked"));
+    assert!(position("-v") < position("--locked"));
+    assert!(position("--locked") < position("--"));
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.get_assembly_path().exists());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_require_lockfile_for_locked_build() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let lockfile_path = Path::new("tests/fixtures/lockless-crate/Cargo.lock");
+    remove_file(lockfile_path).unwrap_or_default();
+
+    let builder = Builder::new("tests/fixtures/lockless-crate")
+        .unwrap()
+        .disable_colors()
+        .with_cargo_arg("--locked");
+
+    match builder.build().unwrap_err().kind() {
+        BuildErrorKind::MissingLockfile(path) => {
+            assert!(path.ends_with("tests/fixtures/lockless-crate"));
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    assert!(!lockfile_path.exists());
+}
+
+#[test]
+fn should_generate_missing_lockfile() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let lockfile_path = Path::new("tests/fixtures/lockless-crate/Cargo.lock");
+    remove_file(lockfile_path).unwrap_or_default();
+
+    let builder = Builder::new("tests/fixtures/lockless-crate")
+        .unwrap()
+        .disable_colors();
+
+    let result = builder.build();
+
+    assert!(lockfile_path.exists());
+
+    match result.unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output
+                .dependencies()
+                .unwrap()
+                .contains(&current_dir().unwrap().join(lockfile_path)));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_forward_env_vars() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let first_builder = Builder::new("tests/fixtures/env-crate")
+        .unwrap()
+        .with_env("PTX_ENV_CRATE_MARKER", "first")
+        .with_env("PTX_CRATE_BUILDING", "0")
+        .disable_colors();
+
+    let second_builder = Builder::new("tests/fixtures/env-crate")
+        .unwrap()
+        .with_env("PTX_ENV_CRATE_MARKER", "second")
+        .disable_colors();
+
+    match (
+        first_builder.build().unwrap(),
+        second_builder.build().unwrap(),
+    ) {
+        (BuildStatus::Success(first_output), BuildStatus::Success(second_output)) => {
+            assert_ne!(
+                first_output.get_assembly_path(),
+                second_output.get_assembly_path()
+            );
+
+            assert!(first_output.get_assembly_path().exists());
+            assert!(second_output.get_assembly_path().exists());
+        }
+
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn should_sanitize_inherited_env_vars() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let saved_build_target = env::var_os("CARGO_BUILD_TARGET");
+
+    env::set_var("CARGO_BUILD_TARGET", "x86_64-unknown-linux-gnu");
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors();
+
+    let keeping_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .keep_inherited_env("CARGO_BUILD_TARGET");
+
+    let sanitized_env = builder.sanitized_env();
+    let kept_sanitized_env = keeping_builder.sanitized_env();
+
+    let result = builder.build().map(|status| match status {
+        BuildStatus::Success(output) => output.get_assembly_path(),
+        BuildStatus::NotNeeded => unreachable!(),
+    });
+
+    match saved_build_target {
+        Some(value) => env::set_var("CARGO_BUILD_TARGET", value),
+        None => env::remove_var("CARGO_BUILD_TARGET"),
+    }
+
+    assert!(sanitized_env.contains(&OsString::from("CARGO_BUILD_TARGET")));
+    assert!(!kept_sanitized_env.contains(&OsString::from("CARGO_BUILD_TARGET")));
+
+    assert!(result.unwrap().exists());
+}
+
+#[test]
+fn should_build_workspace_member() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::with_package("tests/fixtures/workspace", "kernel-b").unwrap();
+
+    assert_eq!(builder.get_crate_name(), "kernel-b");
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let assembly_contents = output.get_assembly_string().unwrap();
+
+            assert!(assembly_contents.contains(".visible .entry the_kernel_b("));
+            assert!(!assembly_contents.contains(".visible .entry the_kernel_a("));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_require_workspace_member() {
+    assert!(Builder::new("tests/fixtures/workspace").is_err());
+    assert!(Builder::with_package("tests/fixtures/workspace", "kernel-c").is_err());
+}
+
+#[test]
+fn should_handle_rebuild_without_changes() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = {
+        Builder::new("tests/fixtures/sample-crate")
+            .unwrap()
+            .disable_colors()
+    };
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => assert!(!output.was_cached()),
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.was_cached());
+
+            let mut assembly_contents = String::new();
+
+            File::open(output.get_assembly_path())
+                .unwrap()
+                .read_to_string(&mut assembly_contents)
+                .unwrap();
+
+            assert!(output
+                .get_assembly_path()
+                .to_string_lossy()
+                .contains("release"));
+
+            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_rebuild_after_source_changes() {
+    let _lock = ENV_MUTEX.lock();
+
+    let crate_path = env::temp_dir().join("ptx-builder-changed-crate");
+    remove_dir_all(&crate_path).unwrap_or_default();
+
+    for file in [
+        "Cargo.toml",
+        ".cargo/config.toml",
+        "src/lib.rs",
+        "src/mod1.rs",
+        "src/mod2.rs",
+    ] {
+        let path = crate_path.join(file);
+
+        create_dir_all(path.parent().unwrap()).unwrap();
+        copy(Path::new("tests/fixtures/sample-crate").join(file), path).unwrap();
+    }
+
+    let target_dir = crate_path.join("target");
+
+    let builder = Builder::new(&crate_path)
+        .unwrap()
+        .with_target_dir(&target_dir)
+        .disable_colors();
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => assert!(!output.was_cached()),
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+
+    // Change the contents, but keep the mtime - so only the contents can tell.
+    let lib_path = crate_path.join("src/lib.rs");
+    let modified = lib_path.metadata().unwrap().modified().unwrap();
+
+    let mut lib_file = OpenOptions::new().append(true).open(&lib_path).unwrap();
+
+    writeln!(
+        lib_file,
+        r#"
+#[no_mangle]
+pub unsafe extern "ptx-kernel" fn the_changed_kernel(y: *mut f64) {{
+    *y.offset(0) = 0.0;
+}}"#
+    )
+    .unwrap();
+
+    lib_file.set_modified(modified).unwrap();
+    drop(lib_file);
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(!output.was_cached());
+
+            assert!(output
+                .kernel_names()
+                .unwrap()
+                .contains(&String::from("the_changed_kernel")));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => assert!(output.was_cached()),
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+
+    remove_dir_all(&crate_path).unwrap_or_default();
+}
+
+#[test]
+fn should_write_assembly_with_custom_profile() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/profile-crate").unwrap();
+
+    match builder
+        .set_profile(Profile::Custom(String::from("kernel")))
+        .disable_colors()
+        .build()
+        .unwrap()
+    {
+        BuildStatus::Success(output) => {
+            let assembly_path = output.get_assembly_path();
+
+            assert!(assembly_path
+                .parent()
+                .unwrap()
+                .ends_with("nvptx64-nvidia-cuda/kernel/deps"));
+
+            assert!(assembly_path.exists());
+
+            assert!(output
+                .kernel_names()
+                .unwrap()
+                .contains(&String::from("the_kernel")));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_write_assembly_in_debug_mode() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder
+        .set_profile(Profile::Debug)
+        .disable_colors()
+        .build()
+        .unwrap()
+    {
+        BuildStatus::Success(output) => {
+            let mut assembly_contents = String::new();
+
+            File::open(output.get_assembly_path())
+                .unwrap()
+                .read_to_string(&mut assembly_contents)
+                .unwrap();
+
+            assert!(output
+                .get_assembly_path()
+                .to_string_lossy()
+                .contains("debug"));
+
+            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+#[ignore = "requires the CUDA toolkit"]
+fn should_build_compute_capabilities_in_parallel() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let compute_capabilities = [ComputeCapability::Sm70, ComputeCapability::Sm86];
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_compute_capabilities(&compute_capabilities)
+        .disable_colors();
+
+    let started_at = Instant::now();
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            // The durations of the separate builds are summed up.
+            assert!(started_at.elapsed() < output.build_duration());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+
+    for compute_capability in compute_capabilities {
+        let builder = Builder::new("tests/fixtures/sample-crate")
+            .unwrap()
+            .set_compute_capability(compute_capability)
+            .disable_colors();
+
+        match builder.build().unwrap() {
+            BuildStatus::Success(output) => {
+                assert!(output.was_cached());
+                assert!(output.get_assembly_path().exists());
+            }
+
+            BuildStatus::NotNeeded => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn should_separate_compute_capabilities() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let sm_70_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_compute_capability(ComputeCapability::Sm70)
+        .disable_colors();
+
+    let sm_86_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_compute_capability(ComputeCapability::Sm86)
+        .disable_colors();
+
+    match (
+        sm_70_builder.build().unwrap(),
+        sm_86_builder.build().unwrap(),
+    ) {
+        (BuildStatus::Success(sm_70_output), BuildStatus::Success(sm_86_output)) => {
+            assert_ne!(
+                sm_70_output.get_assembly_path(),
+                sm_86_output.get_assembly_path()
+            );
+
+            assert!(sm_70_output.get_assembly_path().exists());
+            assert!(sm_86_output.get_assembly_path().exists());
+        }
+
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+#[ignore = "requires a toolchain with the `nvptx-nvidia-cuda` target"]
+fn should_separate_targets() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let nvptx32_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_target(NvptxTarget::Nvptx32)
+        .disable_colors();
+
+    let nvptx64_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_target(NvptxTarget::Nvptx64)
+        .disable_colors();
+
+    match (
+        nvptx32_builder.build().unwrap(),
+        nvptx64_builder.build().unwrap(),
+    ) {
+        (BuildStatus::Success(nvptx32_output), BuildStatus::Success(nvptx64_output)) => {
+            assert_ne!(
+                nvptx32_output.get_assembly_path(),
+                nvptx64_output.get_assembly_path()
+            );
+
+            assert!(nvptx32_output
+                .get_assembly_path()
+                .to_string_lossy()
+                .contains("nvptx-nvidia-cuda"));
+            assert!(nvptx64_output
+                .get_assembly_path()
+                .to_string_lossy()
+                .contains("nvptx64-nvidia-cuda"));
+        }
+
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn should_separate_opt_levels() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let o2_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_opt_level(OptLevel::O2)
+        .disable_colors();
+
+    let oz_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_opt_level(OptLevel::Oz)
+        .disable_colors();
+
+    match (o2_builder.build().unwrap(), oz_builder.build().unwrap()) {
+        (BuildStatus::Success(o2_output), BuildStatus::Success(oz_output)) => {
+            assert_ne!(o2_output.get_assembly_path(), oz_output.get_assembly_path());
+
+            assert!(o2_output
+                .get_assembly_path()
+                .to_string_lossy()
+                .contains("release"));
+            assert!(oz_output
+                .get_assembly_path()
+                .to_string_lossy()
+                .contains("release"));
+        }
+
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn should_limit_build_jobs() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_jobs(1)
+        .disable_colors();
+
+    let default_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors();
+
+    let command = builder.dry_run().unwrap();
+    let target_dir = |command: &[String]| {
+        command
+            .iter()
+            .find(|item| item.starts_with("CARGO_TARGET_DIR="))
+            .cloned()
+    };
+
+    assert!(command.windows(2).any(|pair| pair == ["--jobs", "1"]));
+    assert_eq!(
+        target_dir(&command),
+        target_dir(&default_builder.dry_run().unwrap())
+    );
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.get_assembly_path().exists());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_set_verbosity() {
+    let _lock = ENV_MUTEX.lock();
+
+    let count_stderr_lines = |verbosity| {
+        cleanup_temp_location();
+
+        let builder = Builder::new("tests/fixtures/sample-crate")
+            .unwrap()
+            .set_verbosity(verbosity)
+            .disable_colors();
+
+        let mut lines = 0;
+
+        builder.build_live(|_| (), |_| lines += 1).ok();
+        lines
+    };
+
+    let quiet_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_verbosity(Verbosity::Quiet);
+
+    assert!(quiet_builder
+        .get_cargo_args()
+        .unwrap()
+        .contains(&String::from("-q")));
+    assert!(count_stderr_lines(Verbosity::Verbose) > count_stderr_lines(Verbosity::Quiet));
+}
+
+#[test]
+fn should_set_codegen_units() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_codegen_units(1)
+        .disable_colors();
+
+    let default_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .disable_colors();
+
+    let command = builder.dry_run().unwrap();
+
+    assert!(command
+        .windows(2)
+        .any(|pair| pair == ["-C", "codegen-units=1"]));
+    assert_ne!(command, default_builder.dry_run().unwrap());
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.get_assembly_path().exists());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_set_overflow_checks() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = |on| {
+        Builder::new("tests/fixtures/overflow-crate")
+            .unwrap()
+            .set_overflow_checks(on)
+            .disable_colors()
+    };
+
+    let command = builder(true).dry_run().unwrap();
+
+    assert!(command
+        .windows(2)
+        .any(|pair| pair == ["-C", "overflow-checks=on"]));
+    assert_ne!(command, builder(false).dry_run().unwrap());
+
+    for (on, has_panic_path) in [(true, true), (false, false)] {
+        match builder(on).build().unwrap() {
+            BuildStatus::Success(output) => {
+                assert_eq!(
+                    output.get_assembly_string().unwrap().contains("brkpt"),
+                    has_panic_path
+                );
+            }
+
+            BuildStatus::NotNeeded => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn should_set_ptx_version() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let unsupported_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_ptx_version(9, 9)
+        .disable_colors();
+
+    match unsupported_builder.build().unwrap_err().kind() {
+        BuildErrorKind::UnsupportedPtxVersion {
+            requested,
+            supported,
+        } => {
+            assert_eq!(requested, "9.9");
+            assert!(supported.contains(&String::from("7.0")));
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_ptx_version(7, 0)
+        .disable_colors();
+
+    assert!(builder
+        .get_cargo_args()
+        .unwrap()
+        .contains(&String::from("target-feature=+ptx70")));
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output
+                .get_assembly_string()
+                .unwrap()
+                .lines()
+                .any(|line| line.trim() == ".version 7.0"));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_emit_debug_info() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let full_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_debug_info(DebugInfo::Full)
+        .disable_colors();
+
+    let none_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_debug_info(DebugInfo::None)
+        .disable_colors();
+
+    assert!(full_builder
+        .get_cargo_args()
+        .unwrap()
+        .contains(&String::from("debuginfo=2")));
+
+    match (full_builder.build().unwrap(), none_builder.build().unwrap()) {
+        (BuildStatus::Success(full_output), BuildStatus::Success(none_output)) => {
+            let has_line_info = |assembly: &str| {
+                assembly.lines().any(|line| {
+                    line.trim_start().starts_with(".loc") || line.trim_start().starts_with(".file")
+                })
+            };
+
+            assert_ne!(
+                full_output.get_assembly_path(),
+                none_output.get_assembly_path()
+            );
+
+            assert!(has_line_info(&full_output.get_assembly_string().unwrap()));
+            assert!(!has_line_info(&none_output.get_assembly_string().unwrap()));
+        }
+
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn should_evict_cached_builds() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let output_root = Path::new(env!("OUT_DIR")).join("sample_ptx_crate");
+
+    for opt_level in [OptLevel::O1, OptLevel::O2, OptLevel::O3] {
+        let builder = Builder::new("tests/fixtures/sample-crate")
+            .unwrap()
+            .set_opt_level(opt_level)
+            .with_cache_limit(2)
+            .disable_colors();
+
+        match builder.build().unwrap() {
+            BuildStatus::Success(output) => assert!(output.get_assembly_path().exists()),
+            BuildStatus::NotNeeded => unreachable!(),
+        }
+
+        // File timestamps may be too coarse to order the builds.
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let cached_builds = read_dir(&output_root)
+        .unwrap()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .count();
+
+    assert_eq!(cached_builds, 2);
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_cache_limit(0)
+        .disable_colors();
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => assert!(output.get_assembly_path().exists()),
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+
+    assert_eq!(builder.cached_builds().unwrap().len(), 1);
+}
+
+#[test]
+fn should_list_cached_builds() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let mut assembly_paths = Vec::new();
+
+    for profile in [Profile::Debug, Profile::Release] {
+        let builder = Builder::new("tests/fixtures/sample-crate")
+            .unwrap()
+            .set_profile(profile)
+            .disable_colors();
+
+        match builder.build().unwrap() {
+            BuildStatus::Success(output) => assembly_paths.push(output.get_assembly_path()),
+            BuildStatus::NotNeeded => unreachable!(),
+        }
+    }
+
+    let cached_builds = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .cached_builds()
+        .unwrap();
+
+    assert_eq!(cached_builds.len(), 2);
+
+    for assembly_path in assembly_paths {
+        assert!(cached_builds
+            .iter()
+            .any(|cached_build| assembly_path.starts_with(cached_build)));
+    }
+}
+
+#[test]
+fn should_build_with_panic_abort() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_panic_strategy(PanicStrategy::Abort)
+        .disable_colors();
+
+    assert!(builder
+        .get_cargo_args()
+        .unwrap()
+        .ends_with(&[String::from("-C"), String::from("panic=abort")]));
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output
+                .get_assembly_string()
+                .unwrap()
+                .contains(".visible .entry the_kernel("));
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_shrink_assembly_with_lto() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let off_builder = Builder::new("tests/fixtures/lto-crate")
+        .unwrap()
+        .set_lto(LtoMode::Off)
+        .disable_colors();
+
+    let fat_builder = Builder::new("tests/fixtures/lto-crate")
+        .unwrap()
+        .set_lto(LtoMode::Fat)
+        .disable_colors();
+
+    match (off_builder.build().unwrap(), fat_builder.build().unwrap()) {
+        (BuildStatus::Success(off_output), BuildStatus::Success(fat_output)) => {
+            assert_ne!(
+                off_output.get_assembly_path(),
+                fat_output.get_assembly_path()
+            );
+
+            let off_assembly = off_output.get_assembly_string().unwrap();
+            let fat_assembly = fat_output.get_assembly_string().unwrap();
+
+            assert!(off_assembly.contains(".visible .entry the_kernel("));
+            assert!(fat_assembly.contains(".visible .entry the_kernel("));
+
+            assert!(fat_assembly.len() <= off_assembly.len());
+        }
+
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn should_reject_unknown_compute_capability() {
+    assert_eq!(
+        "sm_86".parse::<ComputeCapability>().unwrap(),
+        ComputeCapability::Sm86
+    );
+
+    match "sm_42".parse::<ComputeCapability>().unwrap_err().kind() {
+        BuildErrorKind::InvalidComputeCapability(compute_capability) => {
+            assert_eq!(compute_capability, "sm_42");
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_pass_offline_and_frozen_flags() {
+    let default_args = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .get_cargo_args()
+        .unwrap();
+
+    assert!(!default_args.contains(&String::from("--offline")));
+    assert!(!default_args.contains(&String::from("--frozen")));
+
+    let args = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .set_offline()
+        .set_frozen()
+        .get_cargo_args()
+        .unwrap();
+
+    let separator = args.iter().position(|arg| arg == "--").unwrap();
+
+    assert!(args[..separator].contains(&String::from("--offline")));
+    assert!(args[..separator].contains(&String::from("--frozen")));
+}
 
-            File::open(output.get_assembly_path())
-                .unwrap()
-                .read_to_string(&mut assembly_contents)
-                .unwrap();
+#[test]
+fn should_report_missing_toolchain() {
+    let _lock = ENV_MUTEX.lock();
 
-            assert!(output
-                .get_assembly_path()
-                .to_string_lossy()
-                .contains("release"));
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_toolchain("almost-unique-toolchain")
+        .disable_colors();
 
-            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+    assert_eq!(
+        builder.get_cargo_args().unwrap()[0],
+        "+almost-unique-toolchain"
+    );
+
+    match builder.build().unwrap_err().kind() {
+        BuildErrorKind::ToolchainNotInstalled { toolchain, hint } => {
+            assert_eq!(toolchain, "almost-unique-toolchain");
+            assert!(hint.contains("rustup toolchain install almost-unique-toolchain"));
         }
 
-        BuildStatus::NotNeeded => unreachable!(),
+        _ => unreachable!("it should fail with proper error"),
     }
 }
 
 #[test]
-fn should_build_mixed_crate_lib() {
+fn should_report_about_build_failure() {
     let _lock = ENV_MUTEX.lock();
 
     cleanup_temp_location();
 
-    let builder = Builder::new("tests/fixtures/mixed-crate").unwrap();
-
-    match builder
-        .set_crate_type(CrateType::Library)
-        .disable_colors()
-        .build()
+    let builder = Builder::new("tests/fixtures/faulty-crate")
         .unwrap()
-    {
-        BuildStatus::Success(output) => {
-            let mut assembly_contents = String::new();
+        .disable_colors();
 
-            println!("{}", output.get_assembly_path().display());
+    let output = builder.build();
+    let lib_path = PathBuf::from("src").join("lib.rs");
 
-            File::open(output.get_assembly_path())
-                .unwrap()
-                .read_to_string(&mut assembly_contents)
-                .unwrap();
+    match output.unwrap_err().kind() {
+        BuildErrorKind::BuildFailed {
+            diagnostics,
+            command,
+            ..
+        } => {
+            assert_eq!(command[..2], ["cargo", "rustc"]);
+            assert!(command.iter().any(|arg| arg == "nvptx64-nvidia-cuda"));
+
+            let diagnostic = diagnostics
+                .iter()
+                .find(|diagnostic| diagnostic.code.as_deref() == Some("E0425"))
+                .expect("it should report the E0425 error");
+
+            assert_eq!(diagnostic.level, "error");
+            assert_eq!(
+                diagnostic.message,
+                "cannot find function `external_fn` in this scope"
+            );
 
-            assert!(output
-                .get_assembly_path()
-                .to_string_lossy()
-                .contains("release"));
+            assert_eq!(diagnostic.spans.len(), 1);
+            assert_eq!(
+                diagnostic.spans[0].file_name,
+                lib_path.display().to_string()
+            );
+            assert_eq!(diagnostic.spans[0].line_start, 7);
+            assert_eq!(diagnostic.spans[0].column_start, 20);
+            assert!(diagnostic.spans[0].is_primary);
 
-            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+            let span = diagnostic.primary_span().unwrap();
+            let snippet = &span.text[0];
+
+            assert_eq!(
+                &snippet.text[snippet.highlight_start - 1..snippet.highlight_end - 1],
+                "external_fn"
+            );
+
+            assert!(diagnostic
+                .github_annotation(Path::new("tests/fixtures/faulty-crate"))
+                .starts_with(&format!(
+                    "::error file={},line=7,endLine=7,col=20,endColumn=31,title=E0425::",
+                    Path::new("tests/fixtures/faulty-crate")
+                        .join(&lib_path)
+                        .display()
+                )));
         }
 
-        BuildStatus::NotNeeded => unreachable!(),
+        _ => unreachable!("it should fail with proper error"),
     }
 }
 
 #[test]
-fn should_handle_rebuild_without_changes() {
+fn should_filter_cargo_output() {
     let _lock = ENV_MUTEX.lock();
 
     cleanup_temp_location();
 
-    let builder = {
-        Builder::new("tests/fixtures/sample-crate")
+    let failed_lines = |builder: Builder| match builder.build().unwrap_err().kind() {
+        BuildErrorKind::BuildFailed { lines, .. } => lines.clone(),
+        _ => unreachable!("it should fail with proper error"),
+    };
+
+    let compiling_line = |line: &String| line.trim_start().starts_with("Compiling faulty");
+
+    let lines = failed_lines(
+        Builder::new("tests/fixtures/faulty-crate")
+            .unwrap()
+            .disable_colors(),
+    );
+
+    assert!(lines.iter().any(compiling_line));
+
+    let lines = failed_lines(
+        Builder::new("tests/fixtures/faulty-crate")
             .unwrap()
             .disable_colors()
-    };
+            .with_diagnostic_filter(|line| !line.contains("Compiling faulty")),
+    );
 
-    builder.build().unwrap();
+    assert!(!lines.is_empty());
+    assert!(!lines.iter().any(compiling_line));
+}
 
-    match builder.build().unwrap() {
-        BuildStatus::Success(output) => {
-            let mut assembly_contents = String::new();
+#[test]
+fn should_merge_env_rustflags() {
+    let _lock = ENV_MUTEX.lock();
 
-            File::open(output.get_assembly_path())
-                .unwrap()
-                .read_to_string(&mut assembly_contents)
-                .unwrap();
+    cleanup_temp_location();
 
-            assert!(output
-                .get_assembly_path()
-                .to_string_lossy()
-                .contains("release"));
+    // `RUSTFLAGS` replace the flags from `.cargo/config.toml`.
+    env::set_var(
+        "RUSTFLAGS",
+        "-Zunstable-options -Clinker-flavor=llbc --cfg env_flag",
+    );
 
-            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+    let builder = Builder::new("tests/fixtures/rustflags-crate")
+        .unwrap()
+        .with_rustc_flag("--cfg builder_flag")
+        .disable_colors();
+
+    let result = builder.build();
+
+    env::remove_var("RUSTFLAGS");
+
+    match result.unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output
+                .kernel_names()
+                .unwrap()
+                .contains(&String::from("the_flagged_kernel")));
         }
 
         BuildStatus::NotNeeded => unreachable!(),
@@ -132,89 +2831,150 @@ fn should_handle_rebuild_without_changes() {
 }
 
 #[test]
-fn should_write_assembly_in_debug_mode() {
+fn should_override_cargo_config() {
     let _lock = ENV_MUTEX.lock();
 
     cleanup_temp_location();
 
-    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+    let builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_cargo_config("profile.release.codegen-units", "1")
+        .disable_colors();
 
-    match builder
-        .set_profile(Profile::Debug)
+    let command = builder.dry_run().unwrap();
+
+    assert!(command
+        .windows(2)
+        .any(|args| args == ["--config", "profile.release.codegen-units=1"]));
+
+    let default_output_path = match Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
         .disable_colors()
         .build()
         .unwrap()
     {
+        BuildStatus::Success(output) => output.get_assembly_path(),
+        BuildStatus::NotNeeded => unreachable!(),
+    };
+
+    match builder.build().unwrap() {
         BuildStatus::Success(output) => {
-            let mut assembly_contents = String::new();
+            assert_ne!(output.get_assembly_path(), default_output_path);
+            assert!(output.get_assembly_path().exists());
+        }
 
-            File::open(output.get_assembly_path())
-                .unwrap()
-                .read_to_string(&mut assembly_contents)
-                .unwrap();
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
 
-            assert!(output
-                .get_assembly_path()
-                .to_string_lossy()
-                .contains("debug"));
+#[test]
+fn should_deny_warnings() {
+    let _lock = ENV_MUTEX.lock();
 
-            assert!(assembly_contents.contains(".visible .entry the_kernel("));
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/warning-crate")
+        .unwrap()
+        .disable_colors()
+        .deny_warnings()
+        .with_rustc_flag("-C debuginfo=0");
+
+    assert!(builder.get_cargo_args().unwrap().ends_with(&[
+        String::from("-D"),
+        String::from("warnings"),
+        String::from("-C"),
+        String::from("debuginfo=0"),
+    ]));
+
+    match builder.build().unwrap_err().kind() {
+        BuildErrorKind::BuildFailed { diagnostics, .. } => {
+            assert!(diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.level == "error"
+                    && diagnostic.message.contains("unused variable")));
         }
 
-        BuildStatus::NotNeeded => unreachable!(),
+        _ => unreachable!("it should fail with proper error"),
     }
 }
 
 #[test]
-fn should_report_about_build_failure() {
+fn should_keep_json_messages_of_failed_build() {
     let _lock = ENV_MUTEX.lock();
 
     cleanup_temp_location();
 
     let builder = Builder::new("tests/fixtures/faulty-crate")
         .unwrap()
-        .disable_colors();
+        .disable_colors()
+        .set_message_format(MessageFormat::Json {
+            render_diagnostics: false,
+            short: false,
+            ansi: false,
+        });
+
+    match builder.build().unwrap_err().kind() {
+        BuildErrorKind::BuildFailed { lines, .. } => {
+            let messages = lines
+                .iter()
+                .filter(|line| line.starts_with('{'))
+                .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+                .collect::<Vec<_>>();
+
+            assert!(messages
+                .iter()
+                .any(|message| message["reason"] == "compiler-message"));
+        }
 
-    let output = builder.build();
-    let crate_absoulte_path = current_dir()
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
+#[test]
+fn should_provide_raw_cargo_output() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/sample-crate")
         .unwrap()
-        .join("tests")
-        .join("fixtures")
-        .join("faulty-crate");
+        .disable_colors();
 
-    let lib_path = PathBuf::from("src").join("lib.rs");
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            let stderr = output.raw_cargo_stderr();
+
+            assert!(stderr.contains("Compiling") || stderr.contains("Finished"));
+            assert!(output
+                .raw_cargo_stdout()
+                .lines()
+                .all(|line| line.starts_with('{')));
+        }
 
-    let crate_absoulte_path_str = crate_absoulte_path.display().to_string();
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
 
-    match output.unwrap_err().kind() {
-        BuildErrorKind::BuildFailed(diagnostics) => {
-            assert_eq!(
-                diagnostics
-                    .iter()
-                    .filter(|item| !item.contains("Blocking waiting")
-                        && !item.contains("Compiling core")
-                        && !item.contains("Compiling compiler_builtins")
-                        && !item.contains("Finished release [optimized] target(s)"))
-                    .collect::<Vec<_>>(),
-                &[
-                    &format!(
-                        "   Compiling faulty-ptx_crate v0.1.0 ({})",
-                        crate_absoulte_path_str
-                    ),
-                    "error[E0425]: cannot find function `external_fn` in this scope",
-                    &format!(" --> {}:7:20", lib_path.display()),
-                    "  |",
-                    "7 |     *y.offset(0) = external_fn(*x.offset(0)) * a;",
-                    "  |                    ^^^^^^^^^^^ not found in this scope",
-                    "",
-                    "For more information about this error, try `rustc --explain E0425`.",
-                    "error: could not compile `faulty-ptx_crate` (lib) due to 1 previous error",
-                    "",
-                ]
-            );
+#[test]
+fn should_provide_warnings() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let builder = Builder::new("tests/fixtures/warning-crate")
+        .unwrap()
+        .disable_colors();
+
+    match builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            let warnings = output.warnings();
+
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(warnings[0].level, "warning");
+            assert!(warnings[0].message.contains("unused variable"));
         }
 
-        _ => unreachable!("it should fail with proper error"),
+        BuildStatus::NotNeeded => unreachable!(),
     }
 }
 
@@ -230,7 +2990,7 @@ fn should_provide_crate_source_files() {
             .join("sample-crate")
     };
 
-    let builder = Builder::new(crate_path.display().to_string()).unwrap();
+    let builder = Builder::new(&crate_path).unwrap();
 
     match builder.disable_colors().build().unwrap() {
         BuildStatus::Success(output) => {
@@ -253,6 +3013,30 @@ fn should_provide_crate_source_files() {
     }
 }
 
+#[test]
+fn should_provide_rerun_directives() {
+    let _lock = ENV_MUTEX.lock();
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::Success(output) => {
+            let directives = output.rerun_directives().unwrap();
+
+            for path in output
+                .dependencies()
+                .unwrap()
+                .into_iter()
+                .chain([output.get_assembly_path()])
+            {
+                assert!(directives.contains(&format!("cargo:rerun-if-changed={}", path.display())));
+            }
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
 #[test]
 fn should_not_get_built_recursively() {
     let _lock = ENV_MUTEX.lock();
@@ -260,6 +3044,11 @@ fn should_not_get_built_recursively() {
     env::set_var("PTX_CRATE_BUILDING", "1");
 
     assert!(!Builder::is_build_needed());
+    assert_eq!(
+        Builder::build_skip_reason(),
+        Some(SkipReason::RecursiveBuild)
+    );
+
     let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
 
     match builder.disable_colors().build().unwrap() {
@@ -270,11 +3059,182 @@ fn should_not_get_built_recursively() {
     env::set_var("PTX_CRATE_BUILDING", "");
 }
 
+#[test]
+fn should_use_distinct_recursion_guards() {
+    let _lock = ENV_MUTEX.lock();
+
+    env::set_var("PTX_OUTER_BUILDING", "1");
+
+    let outer_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_recursion_guard("PTX_OUTER_BUILDING")
+        .disable_colors();
+
+    let inner_builder = Builder::new("tests/fixtures/sample-crate")
+        .unwrap()
+        .with_recursion_guard("PTX_INNER_BUILDING")
+        .disable_colors();
+
+    assert!(Builder::is_build_needed());
+    assert_eq!(
+        outer_builder.get_skip_reason(),
+        Some(SkipReason::RecursiveBuild)
+    );
+
+    match outer_builder.build().unwrap() {
+        BuildStatus::NotNeeded => {}
+        BuildStatus::Success(_) => unreachable!(),
+    }
+
+    assert_eq!(inner_builder.get_skip_reason(), None);
+
+    let command = inner_builder.dry_run().unwrap();
+
+    assert!(command.contains(&String::from("PTX_INNER_BUILDING=1")));
+    assert!(!command.contains(&String::from("PTX_CRATE_BUILDING=1")));
+
+    env::remove_var("PTX_OUTER_BUILDING");
+}
+
+#[test]
+fn should_not_get_built_by_rls() {
+    let _lock = ENV_MUTEX.lock();
+
+    let cargo_env = env::var_os("CARGO");
+
+    env::set_var("CARGO", "/home/user/.cargo/bin/rls");
+
+    assert!(!Builder::is_build_needed());
+    assert_eq!(Builder::build_skip_reason(), Some(SkipReason::Rls));
+
+    let builder = Builder::new("tests/fixtures/sample-crate").unwrap();
+
+    match builder.disable_colors().build().unwrap() {
+        BuildStatus::NotNeeded => {}
+        BuildStatus::Success(_) => unreachable!(),
+    }
+
+    env::set_var("CARGO", "/home/user/.cargo/bin/cargo");
+
+    assert!(Builder::is_build_needed());
+    assert_eq!(Builder::build_skip_reason(), None);
+
+    match cargo_env {
+        Some(cargo_env) => env::set_var("CARGO", cargo_env),
+        None => env::remove_var("CARGO"),
+    }
+}
+
+#[test]
+fn should_build_std_from_source() {
+    let _lock = ENV_MUTEX.lock();
+
+    cleanup_temp_location();
+
+    let core_builder = Builder::new("tests/fixtures/alloc-crate")
+        .unwrap()
+        .disable_colors()
+        .with_build_std(&["core"], &[]);
+
+    let alloc_builder = Builder::new("tests/fixtures/alloc-crate")
+        .unwrap()
+        .disable_colors()
+        .with_build_std(&["core", "alloc"], &["panic_immediate_abort"]);
+
+    let args = alloc_builder.get_cargo_args().unwrap();
+
+    assert!(args
+        .windows(2)
+        .any(|pair| pair == ["-Z", "build-std=core,alloc"]));
+    assert!(args
+        .windows(2)
+        .any(|pair| pair == ["-Z", "build-std-features=panic_immediate_abort"]));
+    assert_ne!(
+        core_builder.dry_run().unwrap(),
+        alloc_builder.dry_run().unwrap()
+    );
+
+    match core_builder.build().unwrap_err().kind() {
+        BuildErrorKind::BuildFailed { diagnostics, .. } => {
+            assert!(diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.message.contains("alloc")));
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+
+    match alloc_builder.build().unwrap() {
+        BuildStatus::Success(output) => {
+            assert!(output.get_assembly_path().exists());
+        }
+
+        BuildStatus::NotNeeded => unreachable!(),
+    }
+}
+
+#[test]
+fn should_link_ptx_modules() {
+    let modules_path = Path::new("tests/fixtures/ptx-modules");
+    let first_path = modules_path.join("first.ptx");
+    let second_path = modules_path.join("second.ptx");
+    let linked_path = Path::new(env!("OUT_DIR")).join("linked.ptx");
+
+    link_ptx(&[&first_path, &second_path], &linked_path).unwrap();
+
+    let mut linked = String::new();
+
+    File::open(&linked_path)
+        .unwrap()
+        .read_to_string(&mut linked)
+        .unwrap();
+
+    assert!(linked.contains(".visible .entry first_kernel("));
+    assert!(linked.contains(".visible .entry second_kernel("));
+    assert!(linked.contains(".func second_helper("));
+    assert_eq!(linked.matches(".version").count(), 1);
+    assert!(linked.contains(".version 7.0"));
+    assert!(linked.contains(".target sm_61"));
+
+    match link_ptx(&[&first_path, &first_path], &linked_path)
+        .unwrap_err()
+        .kind()
+    {
+        BuildErrorKind::LinkFailed { messages } => {
+            assert_eq!(
+                messages,
+                &[String::from(
+                    "`first_kernel` is defined in modules #1 and #2"
+                )]
+            );
+        }
+
+        _ => unreachable!("it should fail with proper error"),
+    }
+}
+
 fn cleanup_temp_location() {
     let crate_names = &[
+        "alloc_ptx_crate",
+        "bins_ptx_crate",
+        "custom_bin_ptx_crate",
+        "env_ptx_crate",
+        "examples_ptx_crate",
         "faulty_ptx_crate",
+        "features_ptx_crate",
+        "kernelless_ptx_crate",
+        "kernel_a",
+        "kernel_b",
+        "locked_ptx_crate",
+        "lockless_ptx_crate",
+        "lto_ptx_crate",
+        "overflow_ptx_crate",
+        "metadata_ptx_crate",
+        "profile_ptx_crate",
+        "rustflags_ptx_crate",
         "sample_app_ptx_crate",
         "sample_ptx_crate",
+        "warning_ptx_crate",
         "mixed_crate",
     ];
 
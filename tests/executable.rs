@@ -1,8 +1,10 @@
-use semver::VersionReq;
+use std::process::Command;
+
+use semver::{Version, VersionReq};
 
 use ptx_builder::{
     error::*,
-    executable::{Cargo, Executable, ExecutableRunner},
+    executable::{Cargo, Executable, ExecutableRunner, Output, Rustc},
 };
 
 mod cargo {
@@ -15,8 +17,10 @@ mod cargo {
             .with_cwd("tests/fixtures/sample-crate")
             .run();
 
-        assert!(output.is_ok());
-        assert_eq!(output.unwrap().stdout, String::from("sample_ptx_crate\n"));
+        let output = output.unwrap();
+
+        assert_eq!(output.stdout, String::from("sample_ptx_crate\n"));
+        assert_eq!(output.stdout_lines(), ["sample_ptx_crate"]);
     }
 
     #[test]
@@ -44,6 +48,76 @@ mod cargo {
     }
 }
 
+mod rustc {
+    use super::*;
+
+    struct UnrealisticRustc;
+
+    impl Executable for UnrealisticRustc {
+        fn get_name(&self) -> String {
+            Rustc.get_name()
+        }
+
+        fn get_verification_hint(&self) -> String {
+            Rustc.get_verification_hint()
+        }
+
+        fn get_version_hint(&self) -> String {
+            Rustc.get_version_hint()
+        }
+
+        fn get_required_version(&self) -> Option<VersionReq> {
+            Some(VersionReq::parse(">= 100.0.0-nightly").unwrap())
+        }
+    }
+
+    #[test]
+    fn should_provide_version() {
+        let output = ExecutableRunner::new(Rustc).with_args(["-V"]).run();
+
+        assert!(output.unwrap().stdout.starts_with("rustc "));
+        assert!(Rustc.get_current_version().is_ok());
+    }
+
+    #[test]
+    fn should_provide_target_list() {
+        let targets = Rustc::target_list(None).unwrap();
+
+        assert!(targets.contains(&String::from("nvptx64-nvidia-cuda")));
+    }
+
+    #[test]
+    fn should_detect_nightly() {
+        // The crate can only be tested with a nightly toolchain.
+        assert!(Rustc::is_nightly(None).unwrap());
+    }
+
+    #[test]
+    fn should_check_version() {
+        let output = ExecutableRunner::new(UnrealisticRustc)
+            .with_args(["-V"])
+            .run();
+
+        match output.unwrap_err().kind() {
+            BuildErrorKind::CommandVersionNotFulfilled {
+                command,
+                required,
+                hint,
+                ..
+            } => {
+                assert_eq!(command, "rustc");
+                assert_eq!(required, &VersionReq::parse(">= 100.0.0-nightly").unwrap());
+                assert_eq!(
+                    hint,
+                    "Please update Rust and Cargo to latest nightly versions"
+                );
+            }
+
+            _ => unreachable!("it should fail with proper error"),
+        }
+    }
+}
+
 mod non_existing_command {
     use super::*;
 
@@ -125,3 +199,277 @@ mod unrealistic_version_requirement {
         }
     }
 }
+
+mod noisy_command {
+    use super::*;
+
+    struct NoisyCommand;
+
+    impl Executable for NoisyCommand {
+        fn get_name(&self) -> String {
+            String::from("sh")
+        }
+
+        fn get_verification_hint(&self) -> String {
+            String::from("Some useful hint")
+        }
+
+        fn get_version_hint(&self) -> String {
+            String::from("Some useful hint about version")
+        }
+
+        fn get_required_version(&self) -> Option<VersionReq> {
+            None
+        }
+
+        fn get_current_version(&self) -> Result<Version> {
+            Ok(Version::new(0, 0, 0))
+        }
+    }
+
+    #[test]
+    fn should_stream_output() {
+        let mut stdout_lines = 0;
+        let mut stderr_lines = 0;
+
+        let output = ExecutableRunner::new(NoisyCommand)
+            .with_args(["tests/fixtures/noisy-command/emit.sh"])
+            .run_live(|_| stdout_lines += 1, |_| stderr_lines += 1);
+
+        assert_eq!(stdout_lines, 50_000);
+        assert_eq!(stderr_lines, 50_001);
+
+        match output.unwrap_err().kind() {
+            BuildErrorKind::CommandFailed { code, stderr, .. } => {
+                assert_eq!(*code, 3);
+
+                assert_eq!(stderr.lines().count(), 50_001);
+                assert!(stderr.starts_with("stderr line 0\n"));
+                assert!(stderr.ends_with("stderr line 49999\nthe last stderr line\n"));
+            }
+
+            _ => unreachable!("it should fail with proper error"),
+        }
+    }
+
+    #[test]
+    fn should_capture_full_stderr() {
+        let output = ExecutableRunner::new(NoisyCommand)
+            .with_args(["tests/fixtures/noisy-command/emit.sh"])
+            .run();
+
+        match output.unwrap_err().kind() {
+            BuildErrorKind::CommandFailed { stderr, .. } => {
+                assert_eq!(stderr.lines().count(), 50_001);
+                assert!(stderr.ends_with("the last stderr line\n"));
+            }
+
+            _ => unreachable!("it should fail with proper error"),
+        }
+    }
+}
+
+mod env_command {
+    use super::*;
+
+    struct EnvCommand;
+
+    impl Executable for EnvCommand {
+        fn get_name(&self) -> String {
+            String::from("env")
+        }
+
+        fn get_verification_hint(&self) -> String {
+            String::from("Some useful hint")
+        }
+
+        fn get_version_hint(&self) -> String {
+            String::from("Some useful hint about version")
+        }
+
+        fn get_required_version(&self) -> Option<VersionReq> {
+            None
+        }
+
+        fn get_current_version(&self) -> Result<Version> {
+            Ok(Version::new(0, 0, 0))
+        }
+    }
+
+    #[test]
+    fn should_layer_env_vars() {
+        let output = ExecutableRunner::new(EnvCommand)
+            .with_envs([
+                (String::from("PTX_FIRST_VAR"), String::from("first")),
+                (String::from("PTX_SECOND_VAR"), String::from("second")),
+            ])
+            .with_env("PTX_SECOND_VAR", "")
+            .with_env("PTX_THIRD_VAR", "third")
+            .run()
+            .unwrap();
+
+        let vars = output.stdout.lines().collect::<Vec<_>>();
+
+        assert!(vars.contains(&"PTX_FIRST_VAR=first"));
+        assert!(vars.contains(&"PTX_SECOND_VAR="));
+        assert!(vars.contains(&"PTX_THIRD_VAR=third"));
+
+        // The environment is inherited.
+        assert!(vars.iter().any(|var| var.starts_with("PATH=")));
+    }
+
+    struct ProbedCommand;
+
+    impl Executable for ProbedCommand {
+        fn get_name(&self) -> String {
+            String::from("sh")
+        }
+
+        fn get_verification_hint(&self) -> String {
+            String::from("Some useful hint")
+        }
+
+        fn get_version_hint(&self) -> String {
+            String::from("Some useful hint about version")
+        }
+
+        fn get_required_version(&self) -> Option<VersionReq> {
+            Some(VersionReq::parse(">= 1.0.0").unwrap())
+        }
+
+        fn get_current_version(&self) -> Result<Version> {
+            Ok(Version::new(0, 0, 0))
+        }
+
+        fn get_current_version_with(&self, mut command: Command) -> Result<Version> {
+            let output = command
+                .args(["-c", "echo \"$PTX_PROBED_VERSION\""])
+                .output()
+                .unwrap();
+
+            Ok(Version::parse(String::from_utf8_lossy(&output.stdout).trim()).unwrap())
+        }
+    }
+
+    #[test]
+    fn should_check_version_in_command_env() {
+        let output = ExecutableRunner::new(ProbedCommand)
+            .with_env("PTX_PROBED_VERSION", "1.2.3")
+            .with_args(["-c", "true"])
+            .run();
+
+        assert!(output.is_ok());
+    }
+}
+
+mod flaky_command {
+    use std::{cell::RefCell, env, fs::remove_file, process, time::Duration};
+
+    use super::*;
+
+    struct FlakyCommand;
+
+    impl Executable for FlakyCommand {
+        fn get_name(&self) -> String {
+            String::from("sh")
+        }
+
+        fn get_verification_hint(&self) -> String {
+            String::from("Some useful hint")
+        }
+
+        fn get_version_hint(&self) -> String {
+            String::from("Some useful hint about version")
+        }
+
+        fn get_required_version(&self) -> Option<VersionReq> {
+            None
+        }
+
+        fn get_current_version(&self) -> Result<Version> {
+            Ok(Version::new(0, 0, 0))
+        }
+    }
+
+    fn run_with_retries(name: &str, retries: u32) -> Result<Output> {
+        let counter_path = env::temp_dir().join(format!("{name}-{}", process::id()));
+
+        remove_file(&counter_path).unwrap_or_default();
+
+        let output = ExecutableRunner::new(FlakyCommand)
+            .with_args(["tests/fixtures/flaky-command/fail-twice.sh"])
+            .with_args([&counter_path])
+            .with_retries(retries, Duration::from_millis(10))
+            .run();
+
+        remove_file(&counter_path).unwrap_or_default();
+
+        output
+    }
+
+    #[test]
+    fn should_retry_transient_failures() {
+        let output = run_with_retries("should_retry_transient_failures", 2).unwrap();
+
+        assert_eq!(output.stdout, "attempt 3\n");
+    }
+
+    #[test]
+    fn should_reset_output_between_retries() {
+        let counter_path = env::temp_dir().join(format!(
+            "should_reset_output_between_retries-{}",
+            process::id()
+        ));
+
+        remove_file(&counter_path).unwrap_or_default();
+
+        let lines = RefCell::new(Vec::new());
+        let mut retries = 0;
+
+        let output = ExecutableRunner::new(FlakyCommand)
+            .with_args(["tests/fixtures/flaky-command/fail-twice.sh"])
+            .with_args([&counter_path])
+            .with_retries(2, Duration::from_millis(10))
+            .run_live_with_reset(
+                |line| lines.borrow_mut().push(line.to_string()),
+                |line| lines.borrow_mut().push(line.to_string()),
+                || {
+                    lines.borrow_mut().clear();
+                    retries += 1;
+                },
+            );
+
+        remove_file(&counter_path).unwrap_or_default();
+
+        assert!(output.is_ok());
+        assert_eq!(retries, 2);
+        assert_eq!(lines.into_inner(), ["attempt 3"]);
+    }
+
+    #[test]
+    fn should_give_up_after_retries() {
+        let output = run_with_retries("should_give_up_after_retries", 1);
+
+        match output.unwrap_err().kind() {
+            BuildErrorKind::CommandFailed { code, stderr, .. } => {
+                assert_eq!(*code, 101);
+                assert!(stderr.contains("spurious network error"));
+            }
+
+            _ => unreachable!("it should fail with proper error"),
+        }
+    }
+
+    #[test]
+    fn should_not_retry_other_failures() {
+        let mut stdout_lines = 0;
+
+        let output = ExecutableRunner::new(FlakyCommand)
+            .with_args(["tests/fixtures/noisy-command/emit.sh"])
+            .with_retries(3, Duration::from_millis(10))
+            .run_live(|_| stdout_lines += 1, |_| {});
+
+        assert!(output.is_err());
+        assert_eq!(stdout_lines, 50_000);
+    }
+}
@@ -0,0 +1,10 @@
+#![feature(abi_ptx)]
+#![no_std]
+#![no_main]
+
+use custom_bin_ptx_crate as _;
+
+#[no_mangle]
+pub unsafe extern "ptx-kernel" fn the_custom_kernel(x: *const f64, y: *mut f64, a: f64) {
+    *y.offset(0) = *x.offset(0) * a;
+}
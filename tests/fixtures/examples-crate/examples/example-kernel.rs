@@ -0,0 +1,9 @@
+#![feature(abi_ptx)]
+#![no_std]
+
+use examples_ptx_crate::scale;
+
+#[no_mangle]
+pub unsafe extern "ptx-kernel" fn the_example_kernel(x: *const f64, y: *mut f64, a: f64) {
+    *y.offset(0) = scale(*x.offset(0), a);
+}
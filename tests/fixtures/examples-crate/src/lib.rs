@@ -0,0 +1,13 @@
+#![allow(internal_features)]
+#![feature(core_intrinsics)]
+#![no_std]
+
+pub fn scale(x: f64, a: f64) -> f64 {
+    x * a
+}
+
+#[panic_handler]
+unsafe fn breakpoint_panic_handler(_: &::core::panic::PanicInfo) -> ! {
+    core::intrinsics::breakpoint();
+    core::hint::unreachable_unchecked();
+}
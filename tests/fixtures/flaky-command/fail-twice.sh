@@ -0,0 +1,12 @@
+#!/bin/sh
+# Fails with a transient network error on the first two runs, counted in "$1".
+
+count=$(($(cat "$1" 2>/dev/null || echo 0) + 1))
+echo "$count" > "$1"
+
+if [ "$count" -le 2 ]; then
+    echo "warning: spurious network error: [6] Couldn't resolve host name" >&2
+    exit 101
+fi
+
+echo "attempt $count"
@@ -0,0 +1,13 @@
+#![no_std]
+
+pub fn polynomial(x: f64) -> f64 {
+    let mut result = 0.0;
+    let mut power = 1.0;
+
+    for coefficient in [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0] {
+        result += coefficient * power;
+        power *= x;
+    }
+
+    result
+}
@@ -0,0 +1,12 @@
+#!/bin/sh
+# Emits a lot of interleaved output and fails.
+
+i=0
+while [ $i -lt 50000 ]; do
+    echo "stdout line $i"
+    echo "stderr line $i" >&2
+    i=$((i + 1))
+done
+
+echo "the last stderr line" >&2
+exit 3
@@ -0,0 +1,14 @@
+#![allow(internal_features)]
+#![feature(abi_ptx, core_intrinsics)]
+#![no_std]
+
+#[no_mangle]
+pub unsafe extern "ptx-kernel" fn the_overflowing_kernel(x: *const u32, y: *mut u32, a: u32) {
+    *y.offset(0) = *x.offset(0) + a;
+}
+
+#[panic_handler]
+unsafe fn breakpoint_panic_handler(_: &::core::panic::PanicInfo) -> ! {
+    core::intrinsics::breakpoint();
+    core::hint::unreachable_unchecked();
+}
@@ -0,0 +1,20 @@
+#![allow(internal_features)]
+#![feature(abi_ptx, core_intrinsics)]
+#![no_std]
+
+#[cfg(not(env_flag))]
+compile_error!("`RUSTFLAGS` from the environment were not passed");
+
+#[cfg(not(builder_flag))]
+compile_error!("`rustc` flags of the builder were not passed");
+
+#[no_mangle]
+pub unsafe extern "ptx-kernel" fn the_flagged_kernel(x: *const f64, y: *mut f64, a: f64) {
+    *y.offset(0) = *x.offset(0) * a;
+}
+
+#[panic_handler]
+unsafe fn breakpoint_panic_handler(_: &::core::panic::PanicInfo) -> ! {
+    core::intrinsics::breakpoint();
+    core::hint::unreachable_unchecked();
+}
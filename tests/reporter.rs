@@ -15,13 +15,17 @@ fn should_report_in_cargo_style() {
     });
 
     let chained_error = chained_error.map_err(|err| {
-        err.context(BuildErrorKind::BuildFailed(vec![
-            String::from("error[E0425]: cannot find function `external_fn` in this scope"),
-            String::from(" --> src/lib.rs:6:20"),
-            String::from("  |"),
-            String::from("6 |     *y.offset(0) = external_fn(*x.offset(0)) * a;"),
-            String::from("  |                    ^^^^^^^^^^^ not found in this scope"),
-        ]))
+        err.context(BuildErrorKind::BuildFailed {
+            diagnostics: Vec::new(),
+            lines: vec![
+                String::from("error[E0425]: cannot find function `external_fn` in this scope"),
+                String::from(" --> src/lib.rs:6:20"),
+                String::from("  |"),
+                String::from("6 |     *y.offset(0) = external_fn(*x.offset(0)) * a;"),
+                String::from("  |                    ^^^^^^^^^^^ not found in this scope"),
+            ],
+            command: Vec::new(),
+        })
     });
 
     let mut reporter = ErrorLogPrinter::print(chained_error.unwrap_err());